@@ -4,10 +4,15 @@
 
 use std::borrow::Cow;
 
-use toml;
-
+use crate::compilation::{diagnostic::Severity, CompilerOutput};
 use crate::prelude::{io, Display, PathBuf};
 
+/// Maximum number of compile errors shown.
+const MAX_SHOWN_ERRORS: usize = 3;
+/// Maximum number of lines of the compiler output shown when no error can be
+/// recognized in it.
+const MAX_SHOWN_LINES: usize = 20;
+
 /// All error variants in OI Checker
 #[derive(Debug)]
 pub enum CheckerError {
@@ -46,6 +51,12 @@ pub enum CheckerError {
         args: Vec<String>,
         file: PathBuf,
         msg: String,
+        /// The output of the compiler, if the command is a compiler.
+        output: Option<CompilerOutput>,
+    },
+    /// Errors of more than one compilation.
+    CompileErrors {
+        errors: Vec<CheckerError>,
     },
     FilterError {
         filter: crate::launch::filter::OutputFilter,
@@ -66,9 +77,75 @@ impl CheckerError {
     /// Print the error message to `stderr` and exit with the provided code
     pub fn destruct(&self) -> ! {
         use std::process;
+        self.report();
+        process::exit(1);
+    }
+
+    /// Print the error message, the details and the help.
+    pub fn report(&self) {
         crate::LOGGER.fatal(&self.to_string());
+        if let Some(details) = self.get_details() {
+            crate::LOGGER.info(&details);
+        }
         crate::LOGGER.info(&format!("Help: {}", self.get_help()));
-        process::exit(1);
+    }
+
+    /// Get the details shown after the error: the first errors of the
+    /// compiler, or the beginning of its output if no error is recognized.
+    pub fn get_details(&self) -> Option<String> {
+        let output = match self {
+            Self::CommandError {
+                output: Some(output),
+                ..
+            } => output,
+            Self::CompileErrors { errors } => {
+                let details: Vec<String> = errors.iter().filter_map(Self::get_details).collect();
+                return (!details.is_empty()).then(|| details.join("\n"));
+            }
+            _ => return None,
+        };
+        let errors: Vec<String> = output
+            .get(Severity::Error)
+            .map(|diagnostic| diagnostic.to_string())
+            .collect();
+        let shown = if errors.is_empty() {
+            output
+                .text
+                .lines()
+                .take(MAX_SHOWN_LINES)
+                .collect::<Vec<_>>()
+                .join("\n")
+        } else {
+            errors
+                .iter()
+                .take(MAX_SHOWN_ERRORS)
+                .cloned()
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        let more = if errors.len() > MAX_SHOWN_ERRORS {
+            format!(" ({} errors in total)", errors.len())
+        } else {
+            String::new()
+        };
+        Some(format!(
+            "Compiler output (saved to file {}){}:\n{}",
+            output.log_file.display(),
+            more,
+            shown
+        ))
+    }
+
+    /// Get the compiler output file of the error, if any.
+    pub fn get_log_file(&self) -> Option<&PathBuf> {
+        match self {
+            Self::CommandError {
+                output: Some(output),
+                ..
+            } => Some(&output.log_file),
+            Self::CompileErrors { errors } => errors.iter().find_map(Self::get_log_file),
+            _ => None,
+        }
     }
 
     pub fn get_help(&self) -> std::borrow::Cow<'static, str> {
@@ -94,7 +171,9 @@ impl CheckerError {
                     .collect::<Vec<_>>()
                     .join("\n")
             )),
-            Self::CommandError { .. } => B("Check your program or config."),
+            Self::CommandError { .. } | Self::CompileErrors { .. } => {
+                B("Check your program or config.")
+            }
             Self::FilterError { .. } => B("This error shouldn't occur, it's a TOC-TOU error."),
             Self::DiffToolError { .. } => B("Please check if the different tool program exists."),
         }
@@ -152,6 +231,7 @@ impl Display for CheckerError {
                 args,
                 file,
                 msg,
+                ..
             } => write!(
                 f,
                 "Error during {} (file: {}): {}.\n\
@@ -165,6 +245,13 @@ impl Display for CheckerError {
                     .collect::<Vec<_>>()
                     .join(" ")
             ),
+            CompileErrors { errors } => {
+                write!(f, "{} programs failed to compile:", errors.len())?;
+                for (i, err) in errors.iter().enumerate() {
+                    write!(f, "\n{}. {}", i + 1, err)?;
+                }
+                Ok(())
+            }
             FilterError { filter, err, file } => write!(
                 f,
                 "Error during filtering file {} (filter: {}): {}",
@@ -195,9 +282,12 @@ pub enum Stage {
     CompileDG,
     CompileAC,
     CompileTP,
+    CompileCK,
+    CompileIT,
     LaunchDG,
     LaunchAC,
     LaunchTP,
+    LaunchIT,
 }
 
 impl Display for Stage {
@@ -207,9 +297,12 @@ impl Display for Stage {
             CompileDG => "compiling data generator",
             CompileAC => "compiling accepted program",
             CompileTP => "compiling tested program",
+            CompileCK => "compiling checker",
+            CompileIT => "compiling interactor",
             LaunchDG => "launching data generator",
             LaunchAC => "launching accepted program",
             LaunchTP => "launching tested program",
+            LaunchIT => "launching interactor",
         };
         write!(f, "{}", s)
     }
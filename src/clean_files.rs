@@ -1,9 +1,11 @@
 //! Clean the files after launching.
 
 use crate::prelude::*;
+use serde::Serialize;
 
 /// Clean files setting.
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum AutoRemoveFiles {
     AC,
     Always,
@@ -11,18 +13,43 @@ pub enum AutoRemoveFiles {
 }
 
 impl AutoRemoveFiles {
-    /// Clean the files.
+    /// Clean the files of the finished suites. `programs` is the number of
+    /// tested programs, whose output files are tagged if there are more than
+    /// one.
+    ///
+    /// The working directory may be removed only if `all_finished`, since the
+    /// files of the unfinished suites (and the manifest) are kept.
     pub fn run(
         &self,
         ac_launch_indexes: Vec<u32>,
-        test_cases: u32,
+        finished_indexes: Vec<u32>,
+        all_finished: bool,
+        programs: usize,
         work_dir: &PathBuf,
         created_work_dir: bool,
     ) -> Result<(), io::Error> {
+        let tags: Vec<String> = match programs {
+            1 => vec![String::new()],
+            _ => (1..=programs).map(|k| format!("-{}", k)).collect(),
+        };
         let remove_suite = |i: u32| -> io::Result<()> {
-            fs::remove_file(work_dir.join(format!("data{}.in", i)))?;
-            fs::remove_file(work_dir.join(format!("ac{}.out", i)))?;
-            fs::remove_file(work_dir.join(format!("tested{}.out", i)))?;
+            let mut file_names = vec![format!("data{}.in", i), format!("ac{}.out", i)];
+            for tag in tags.iter() {
+                file_names.extend([
+                    format!("tested{}{}.out", i, tag),
+                    format!("data{}{}.min.in", i, tag),
+                    format!("ac{}{}.min.out", i, tag),
+                    format!("tested{}{}.min.out", i, tag),
+                ]);
+            }
+            // Some files are not produced in every suite (e.g. `ac{n}.out` of an
+            // interactive problem), so missing files are skipped.
+            for file_name in file_names {
+                match fs::remove_file(work_dir.join(file_name)) {
+                    Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+                    _ => (),
+                }
+            }
             Ok(())
         };
 
@@ -38,11 +65,16 @@ impl AutoRemoveFiles {
         match self {
             Self::Never => Ok(()),
             Self::Always => {
-                for i in 1..=test_cases {
-                    remove_suite(i)?;
+                for i in finished_indexes.iter() {
+                    remove_suite(*i)?;
+                }
+                crate::LOGGER.info(&format!(
+                    "Remove all {} generated files.",
+                    finished_indexes.len()
+                ));
+                if all_finished {
+                    remove_all()?;
                 }
-                crate::LOGGER.info(&format!("Remove all {} generated files.", test_cases));
-                remove_all()?;
                 Ok(())
             }
             Self::AC => {
@@ -53,7 +85,7 @@ impl AutoRemoveFiles {
                     "Remove {} generated files.",
                     ac_launch_indexes.len()
                 ));
-                if ac_launch_indexes.len() == test_cases as usize {
+                if all_finished && ac_launch_indexes.len() == finished_indexes.len() {
                     remove_all()?;
                 }
                 Ok(())
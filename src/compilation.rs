@@ -1,9 +1,14 @@
 //! Compile program source files.
 
+pub mod diagnostic;
+
 use crate::config::{cf_parsing, dynamic_format};
 use crate::prelude::*;
+use diagnostic::{Diagnostic, Severity};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct CompilationConfig {
     pub target: String,
     pub optimize_flag: String,
@@ -22,6 +27,34 @@ impl From<cf_parsing::CompilationConfig> for CompilationConfig {
     }
 }
 
+/// The target of a successful compilation.
+pub struct Compiled {
+    pub target: String,
+    /// Whether the compilation is skipped since the cached target is up to
+    /// date.
+    pub cached: bool,
+    /// The output of the compiler. `None` if it is cached.
+    pub output: Option<CompilerOutput>,
+}
+
+/// The captured output (stdout and stderr) of a compiler.
+#[derive(Debug)]
+pub struct CompilerOutput {
+    /// The file which the output is saved to.
+    pub log_file: PathBuf,
+    pub text: String,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl CompilerOutput {
+    /// Get the diagnostics of the severity.
+    pub fn get(&self, severity: Severity) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics
+            .iter()
+            .filter(move |diagnostic| diagnostic.severity == severity)
+    }
+}
+
 impl CompilationConfig {
     /// Get arguments of the compilation.
     ///
@@ -67,32 +100,104 @@ impl CompilationConfig {
         Ok((target, args))
     }
 
+    /// Get the key of the compilation cache: a hash of the source content,
+    /// the command, the arguments and the compiler version (the output of
+    /// `<command> --version`).
+    ///
+    /// Return `None` if the source can't be read.
+    fn get_cache_key(&self, file: &PathBuf, args: &[String]) -> Option<String> {
+        let source = fs::read(file).ok()?;
+        let version = Command::new(&self.command)
+            .arg("--version")
+            .stdin(Stdio::null())
+            .output()
+            .map(|output| [output.stdout, output.stderr].concat())
+            .unwrap_or_default();
+        let mut hasher = Sha256::new();
+        // Lengths are hashed before the contents to separate the fields.
+        for field in [source.as_slice(), self.command.as_bytes(), &version]
+            .into_iter()
+            .chain(args.iter().map(|arg| arg.as_bytes()))
+        {
+            hasher.update((field.len() as u64).to_le_bytes());
+            hasher.update(field);
+        }
+        Some(
+            hasher
+                .finalize()
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect(),
+        )
+    }
+
     /// Compile the program with the config.
+    ///
+    /// The key of the compilation cache is stored in `<target>.hash`. If the
+    /// target exists and the key is not changed, the compilation is skipped.
+    ///
+    /// The output of the compiler is saved to `compile_<filename>.log` in the
+    /// working folder.
     pub fn run(
         &self,
         work_folder: &PathBuf,
         file: &PathBuf,
         stage: Stage,
-    ) -> CheckerResult<String> {
+    ) -> CheckerResult<Compiled> {
         let (target, args) = self.get_args(work_folder, file, stage)?;
-        let error = |msg: String| {
+        let cache_key = self.get_cache_key(file, &args);
+        let cache_file = PathBuf::from(format!("{}.hash", target));
+        if let Some(cache_key) = &cache_key {
+            if PathBuf::from(&target).is_file()
+                && fs::read_to_string(&cache_file).is_ok_and(|key| &key == cache_key)
+            {
+                return Ok(Compiled {
+                    target,
+                    cached: true,
+                    output: None,
+                });
+            }
+        }
+        let error = |msg: String, output: Option<CompilerOutput>| {
             Box::new(CheckerError::CommandError {
                 stage,
                 command: self.command.to_owned(),
                 args: args.to_owned(),
                 file: file.to_owned(),
                 msg,
+                output,
             })
         };
         let output = Command::new(&self.command)
-            .stderr(Stdio::inherit())
+            .stdin(Stdio::null())
             .args(args.clone())
             .output()
-            .map_err(|e| error(format!("IOError: {}", e.to_string())))?;
+            .map_err(|e| error(format!("IOError: {}", e), None))?;
+        let text = String::from_utf8_lossy(&[output.stdout, output.stderr].concat()).to_string();
+        let log_file = work_folder.join(format!(
+            "compile_{}.log",
+            file.file_name().unwrap_or_default().to_string_lossy()
+        ));
+        fs::write(&log_file, &text).ignore();
+        let compiler_output = CompilerOutput {
+            log_file,
+            diagnostics: diagnostic::parse(&text),
+            text,
+        };
         if output.status.success() {
-            Ok(target)
+            if let Some(cache_key) = cache_key {
+                fs::write(&cache_file, cache_key).ignore();
+            }
+            Ok(Compiled {
+                target,
+                cached: false,
+                output: Some(compiler_output),
+            })
         } else {
-            Err(error(format!("Compiler exited with {}", output.status)))
+            Err(error(
+                format!("Compiler exited with {}", output.status),
+                Some(compiler_output),
+            ))
         }
     }
 }
@@ -0,0 +1,141 @@
+//! Parse the diagnostics (errors and warnings) in the output of a compiler.
+//!
+//! GCC and Clang style diagnostics like `file:line:column: error: message` are
+//! recognized, with the source lines printed after them as the context.
+
+use crate::prelude::*;
+
+/// Severity of a diagnostic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// An error or warning reported by the compiler.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub file: String,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+    pub severity: Severity,
+    pub message: String,
+    /// Lines printed after the diagnostic, like the source line and the caret.
+    pub context: Vec<String>,
+}
+
+/// Split a location like `file:line:column` or `file:line`. The file may
+/// contain `:` itself, e.g. `C:\a.cpp`.
+fn parse_location(location: &str) -> (String, Option<u32>, Option<u32>) {
+    let parts: Vec<&str> = location.rsplitn(3, ':').collect();
+    match parts.as_slice() {
+        [column, line, file] => match (line.parse(), column.parse()) {
+            (Ok(line), Ok(column)) => (file.to_string(), Some(line), Some(column)),
+            (_, Ok(line)) => (format!("{}:{}", file, parts[1]), Some(line), None),
+            _ => (location.to_string(), None, None),
+        },
+        [line, file] => match line.parse() {
+            Ok(line) => (file.to_string(), Some(line), None),
+            Err(_) => (location.to_string(), None, None),
+        },
+        _ => (location.to_string(), None, None),
+    }
+}
+
+/// Parse a line like `file:line:column: error: message`.
+fn parse_line(line: &str) -> Option<Diagnostic> {
+    for (marker, severity) in [
+        (": fatal error: ", Severity::Error),
+        (": error: ", Severity::Error),
+        (": warning: ", Severity::Warning),
+    ] {
+        if let Some((location, message)) = line.split_once(marker) {
+            let (file, line, column) = parse_location(location);
+            return Some(Diagnostic {
+                file,
+                line,
+                column,
+                severity,
+                message: message.to_string(),
+                context: Vec::new(),
+            });
+        }
+    }
+    None
+}
+
+/// Parse all diagnostics in the output of a compiler.
+pub fn parse(output: &str) -> Vec<Diagnostic> {
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+    // Whether the following indented lines belong to the last diagnostic.
+    let mut in_context = false;
+    for line in output.lines() {
+        if let Some(diagnostic) = parse_line(line) {
+            diagnostics.push(diagnostic);
+            in_context = true;
+        } else if in_context && line.starts_with(' ') {
+            if let Some(last) = diagnostics.last_mut() {
+                last.context.push(line.to_string());
+            }
+        } else {
+            in_context = false;
+        }
+    }
+    diagnostics
+}
+
+impl Display for Diagnostic {
+    /// Show the diagnostic with the location and severity highlighted.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut location = self.file.clone();
+        for number in [self.line, self.column].into_iter().flatten() {
+            location.push_str(&format!(":{}", number));
+        }
+        let severity = match self.severity {
+            Severity::Error => console::style("error:").red().bold(),
+            Severity::Warning => console::style("warning:").yellow().bold(),
+        };
+        write!(
+            f,
+            "{}: {} {}",
+            console::style(location).bold().underlined(),
+            severity,
+            self.message
+        )?;
+        for line in self.context.iter() {
+            write!(f, "\n{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let output = "\
+a.cpp: In function 'int main()':
+a.cpp:3:5: error: 'x' was not declared in this scope
+    3 |     x = 1;
+      |     ^
+C:\\b.cpp:7: warning: unused variable 'y'
+ld: fatal error: cannot find -lfoo
+";
+        let diagnostics = parse(output);
+        assert_eq!(diagnostics.len(), 3);
+        assert_eq!(diagnostics[0].file, "a.cpp");
+        assert_eq!(diagnostics[0].line, Some(3));
+        assert_eq!(diagnostics[0].column, Some(5));
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert_eq!(diagnostics[0].context.len(), 2);
+        assert_eq!(diagnostics[1].file, "C:\\b.cpp");
+        assert_eq!(diagnostics[1].line, Some(7));
+        assert_eq!(diagnostics[1].column, None);
+        assert_eq!(diagnostics[1].severity, Severity::Warning);
+        assert_eq!(diagnostics[2].file, "ld");
+        assert_eq!(diagnostics[2].line, None);
+        assert_eq!(diagnostics[2].message, "cannot find -lfoo");
+    }
+}
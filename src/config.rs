@@ -9,8 +9,12 @@ use crate::clean_files::AutoRemoveFiles;
 use crate::compilation::CompilationConfig;
 use crate::launch::diff_tool::DiffTool;
 use crate::launch::filter::OutputFilter;
-use crate::launch::LaunchConfig;
+use crate::launch::test_data::TestData;
+use crate::launch::{FileIo, LaunchConfig, TimeMeasurement};
+use crate::report::Report;
+use crate::score::Subtask;
 use dyn_formatting::{self, DynamicFormatErrorKind};
+use serde::Serialize;
 
 const CONFIG_FILE_DEFAULT: &'static str = include_str!("../config_default.toml");
 
@@ -34,21 +38,87 @@ pub fn get_config() -> CheckerResult<Config> {
         };
     }
 
-    let tested_program = get_default!(tested_program);
+    let tested_programs: Vec<PathBuf> = cla_config
+        .tested_program
+        .unwrap_or_else(|| cf_config.default.tested_program.into());
+    if tested_programs.is_empty() {
+        return Err(error!(
+            "Expected at least one program in field `tested_program`".into()
+        ));
+    }
     let accepted_program = get_default!(accepted_program);
     let data_generator = get_default!(data_generator);
-    let test_cases = get_default!(test_cases);
+    let interactor = Some(get_default!(interactor)).filter(|p| !p.as_os_str().is_empty());
+    let test_data = match get_default!(test_data).as_str() {
+        "" => None,
+        pattern => Some(TestData::find(pattern).map_err(|msg| error!(msg))?),
+    };
+    let test_cases = match &test_data {
+        Some(test_data) => test_data.len() as u32,
+        None => get_default!(test_cases),
+    };
+    let until_fail = get_default!(until_fail).then(|| UntilFail {
+        max_cases: match (
+            Some(get_default!(max_cases)).filter(|n| *n != 0),
+            &test_data,
+        ) {
+            (max_cases, None) => max_cases,
+            (max_cases, Some(_)) => {
+                Some(max_cases.map_or(test_cases, |max_cases| max_cases.min(test_cases)))
+            }
+        },
+        time_budget: Some(get_default!(time_budget))
+            .filter(|n| *n != 0)
+            .map(Duration::from_secs),
+    });
+    let seed = match get_default!(seed) {
+        0 => random_seed(),
+        seed => seed,
+    };
+    let minimize = get_default!(minimize);
+    let resume = get_default!(resume);
+    let watch = get_default!(watch);
     let test_threads = get_default!(test_threads);
-    let ac_timeout = Duration::from_millis(get_default!(ac_timeout).into());
+    let ac_timeout = Duration::from_millis(get_default!(ac_timeout));
+    let time_measurement: TimeMeasurement = get_default!(time_measurement)
+        .as_str()
+        .try_into()
+        .map_err(|msg| error!(msg))?;
     let program_timeout = {
-        let program_timeout = Duration::from_millis(get_default!(program_timeout).into());
+        let program_timeout = Duration::from_millis(get_default!(program_timeout));
         if program_timeout >= ac_timeout {
             program_timeout
         } else {
             ac_timeout
         }
     };
+    let memory_limit = match get_default!(memory_limit) {
+        0 => None,
+        megabytes => Some(megabytes * 1024),
+    };
+    let sandbox = get_default!(sandbox);
+    if sandbox && !crate::launch::sandbox::SUPPORTED {
+        return Err(error!(
+            "The sandbox is only supported on Linux (x86_64 and aarch64)".into()
+        ));
+    }
+    let file_io = get_file_io(
+        get_default!(input_file_name),
+        get_default!(output_file_name),
+    )
+    .map_err(|msg| error!(msg))?;
+    let accepted_file_io = get_file_io(
+        cf_config.default.accepted_input_file_name,
+        cf_config.default.accepted_output_file_name,
+    )
+    .map_err(|msg| error!(msg))?;
+    if interactor.is_some() && file_io.is_used() {
+        return Err(error!(
+            "Field `input_file_name` and `output_file_name` can't be used with `interactor`".into()
+        ));
+    }
     let working_directory = get_default!(working_directory);
+    let compile_warnings = get_default!(compile_warnings);
     let auto_remove_files: AutoRemoveFiles = get_default!(auto_remove_files)
         .as_str()
         .try_into()
@@ -63,6 +133,24 @@ pub fn get_config() -> CheckerResult<Config> {
     let diff_tool: DiffTool = get_default!(diff_tool)
         .try_into()
         .map_err(|msg: String| error!(msg))?;
+    let report: Vec<Report> = {
+        let mut report = Vec::new();
+        for item in get_default!(report) {
+            report.push(item.as_str().try_into().map_err(|msg| error!(msg))?);
+        }
+        report
+    };
+    let subtasks: Vec<Subtask> = {
+        let mut subtasks = Vec::new();
+        for subtask in cf_config.subtask {
+            subtasks.push(subtask.try_into().map_err(|msg| error!(msg))?);
+        }
+        subtasks
+    };
+    let replay = match &cla_config.replay {
+        Some(case) => Some(get_replay_index(case, &test_data).map_err(|msg| error!(msg))?),
+        None => None,
+    };
     let compilation_rules: ExtensionRules<CompilationConfig> = cf_config
         .compilation
         .into_iter()
@@ -76,42 +164,174 @@ pub fn get_config() -> CheckerResult<Config> {
         .collect::<Vec<_>>()
         .into();
     Ok(Config {
-        tested_program,
+        tested_programs,
         accepted_program,
         data_generator,
+        interactor,
+        test_data,
         test_cases,
+        until_fail,
+        seed,
+        minimize,
+        resume,
+        watch,
         test_threads,
         ac_timeout,
+        time_measurement,
         program_timeout,
+        memory_limit,
+        sandbox,
+        file_io,
+        accepted_file_io,
         working_directory,
+        compile_warnings,
         auto_remove_files,
         output_filters,
         diff_tool,
+        report,
+        subtasks,
+        replay,
         compilation_rules,
         launch_rules,
     })
 }
 
 /// main configuration
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct Config {
-    pub tested_program: PathBuf,
+    /// The programs which will be tested, compared with the same inputs and
+    /// accepted outputs.
+    pub tested_programs: Vec<PathBuf>,
     pub accepted_program: PathBuf,
     pub data_generator: PathBuf,
+    /// The interactor of an interactive problem. `None` means the problem is
+    /// not interactive.
+    pub interactor: Option<PathBuf>,
+    /// Fixed test data used instead of the data generator. `test_cases` is the
+    /// number of them if provided.
+    pub test_data: Option<Vec<TestData>>,
     pub test_cases: u32,
+    /// Stress-test mode: launch new test suites until the first failing one.
+    /// `test_cases` is ignored if set.
+    pub until_fail: Option<UntilFail>,
+    /// Base seed of the test cases. The seed of each test case is derived
+    /// from it (see `crate::launch::get_case_seed`).
+    pub seed: u64,
+    /// Minimize the input of each WA test case.
+    pub minimize: bool,
+    /// Skip the suites recorded in the manifest of the previous run.
+    pub resume: bool,
+    /// Rerun all suites whenever a source file changes.
+    pub watch: bool,
     pub test_threads: u32,
+    #[serde(serialize_with = "crate::util::serialize_millis")]
     pub ac_timeout: Duration,
+    pub time_measurement: TimeMeasurement,
+    #[serde(serialize_with = "crate::util::serialize_millis")]
     pub program_timeout: Duration,
+    /// Memory limit of the tested program in KiB. `None` means no limit.
+    #[serde(rename = "memory_limit_kib")]
+    pub memory_limit: Option<u64>,
+    /// Run the tested programs in the sandbox (see `crate::launch::sandbox`).
+    pub sandbox: bool,
+    /// The files which the tested programs read and write instead of stdin
+    /// and stdout.
+    pub file_io: FileIo,
+    /// The same as `file_io`, but for the accepted program.
+    pub accepted_file_io: FileIo,
     pub working_directory: PathBuf,
+    /// Summarize the warnings of successful compilations.
+    pub compile_warnings: bool,
     pub auto_remove_files: AutoRemoveFiles,
     pub output_filters: Vec<OutputFilter>,
     pub diff_tool: DiffTool,
+    pub report: Vec<Report>,
+    /// Subtasks defined in the config file. All test cases are scored equally
+    /// if empty.
+    pub subtasks: Vec<Subtask>,
+    /// The index of the test case to replay (`replay` subcommand). `None`
+    /// means all test cases are launched.
+    pub replay: Option<u32>,
     pub compilation_rules: ExtensionRules<CompilationConfig>,
     pub launch_rules: ExtensionRules<LaunchConfig>,
 }
 
+/// Limits of the stress-test mode.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct UntilFail {
+    /// Maximum number of test cases. `None` means no limit.
+    pub max_cases: Option<u32>,
+    /// No new test suite is launched after this duration. `None` means no
+    /// limit.
+    #[serde(serialize_with = "crate::util::serialize_optional_secs")]
+    pub time_budget: Option<Duration>,
+}
+
+/// Get the index of the test case to replay from its index or input file
+/// (`data{n}.in`, or one of the fixed test data).
+fn get_replay_index(case: &str, test_data: &Option<Vec<TestData>>) -> Result<u32, String> {
+    let index = match case.parse::<u32>() {
+        Ok(index) => Some(index),
+        Err(_) => match test_data {
+            Some(test_data) => {
+                let path = PathBuf::from(case);
+                test_data
+                    .iter()
+                    .position(|test_data| {
+                        test_data.input == path
+                            || test_data.input.file_name() == Some(path.as_os_str())
+                    })
+                    .map(|i| i as u32 + 1)
+            }
+            None => PathBuf::from(case)
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_prefix("data"))
+                .and_then(|name| name.strip_suffix(".in"))
+                .and_then(|index| index.parse().ok()),
+        },
+    };
+    let max_index = test_data
+        .as_ref()
+        .map_or(u32::MAX, |test_data| test_data.len() as u32);
+    match index {
+        Some(index) if (1..=max_index).contains(&index) => Ok(index),
+        _ => Err(format!(
+            "`{}` is not a test case to replay. Expected an index (starting from 1) \
+            or an input file like `data1.in`",
+            case
+        )),
+    }
+}
+
+/// Get the file I/O of a program from the names of its input and output
+/// files. An empty name means stdin or stdout.
+fn get_file_io(input_file_name: String, output_file_name: String) -> Result<FileIo, String> {
+    let check = |name: String| match name.as_str() {
+        "" => Ok(None),
+        _ if PathBuf::from(&name).file_name() == Some(name.as_ref()) => Ok(Some(name)),
+        _ => Err(format!(
+            "`{}` is not allowed as an input or output file name. Expected a file name \
+            without directories",
+            name
+        )),
+    };
+    Ok(FileIo {
+        input_file_name: check(input_file_name)?,
+        output_file_name: check(output_file_name)?,
+    })
+}
+
+/// Get a random base seed of the test cases, which is not 0.
+fn random_seed() -> u64 {
+    use std::collections::hash_map::RandomState;
+    use std::hash::{BuildHasher, Hasher};
+    // Keep the seed short enough to be typed.
+    (RandomState::new().build_hasher().finish() >> 32).max(1)
+}
+
 /// Manage rules that is matched by extension names like `launch` and `compilation`
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ExtensionRules<T> {
     store: Vec<T>,
     mapping: HashMap<String, usize>,
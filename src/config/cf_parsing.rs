@@ -3,7 +3,6 @@
 use crate::prelude::*;
 use serde::Deserialize;
 use std::env;
-use toml;
 
 fn get_config_filename() -> PathBuf {
     let program_dir = env::current_exe()
@@ -55,22 +54,81 @@ pub struct Config {
     pub default: DefaultConfig,
     pub compilation: Vec<CompilationConfig>,
     pub launch: Vec<LaunchConfig>,
+    #[serde(default)]
+    pub subtask: Vec<SubtaskConfig>,
 }
 
 /// `default` field in toml file
 #[derive(Deserialize, Debug)]
 pub struct DefaultConfig {
-    pub tested_program: PathBuf,
+    pub tested_program: OneOrMany<PathBuf>,
     pub accepted_program: PathBuf,
     pub data_generator: PathBuf,
+    #[serde(default)]
+    pub interactor: PathBuf,
+    #[serde(default)]
+    pub test_data: String,
     pub test_cases: u32,
+    #[serde(default)]
+    pub until_fail: bool,
+    #[serde(default)]
+    pub max_cases: u32,
+    #[serde(default)]
+    pub time_budget: u64,
+    #[serde(default)]
+    pub seed: u64,
+    #[serde(default)]
+    pub minimize: bool,
+    #[serde(default)]
+    pub resume: bool,
+    #[serde(default)]
+    pub watch: bool,
     pub test_threads: u32,
     pub ac_timeout: u64,
+    #[serde(default = "default_time_measurement")]
+    pub time_measurement: String,
     pub program_timeout: u64,
+    #[serde(default)]
+    pub memory_limit: u64,
+    #[serde(default)]
+    pub sandbox: bool,
+    #[serde(default)]
+    pub input_file_name: String,
+    #[serde(default)]
+    pub output_file_name: String,
+    #[serde(default)]
+    pub accepted_input_file_name: String,
+    #[serde(default)]
+    pub accepted_output_file_name: String,
     pub working_directory: PathBuf,
+    #[serde(default)]
+    pub compile_warnings: bool,
     pub auto_remove_files: String,
     pub output_filters: Vec<String>,
     pub diff_tool: Vec<String>,
+    #[serde(default)]
+    pub report: Vec<String>,
+}
+
+/// A value or an array of values.
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+pub enum OneOrMany<T> {
+    One(T),
+    Many(Vec<T>),
+}
+
+impl<T> From<OneOrMany<T>> for Vec<T> {
+    fn from(value: OneOrMany<T>) -> Self {
+        match value {
+            OneOrMany::One(value) => vec![value],
+            OneOrMany::Many(values) => values,
+        }
+    }
+}
+
+fn default_time_measurement() -> String {
+    "cpu".into()
 }
 
 /// `compile` field in toml file
@@ -90,3 +148,16 @@ pub struct LaunchConfig {
     pub command: String,
     pub args: Vec<String>,
 }
+
+/// `subtask` field in toml file
+#[derive(Deserialize, Debug)]
+pub struct SubtaskConfig {
+    pub range: [u32; 2],
+    pub points: f64,
+    #[serde(default = "default_policy")]
+    pub policy: String,
+}
+
+fn default_policy() -> String {
+    "sum".into()
+}
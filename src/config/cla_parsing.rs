@@ -15,14 +15,18 @@ pub fn parse_cla() -> ClaConfig {
     let app = Command::new(env!("CARGO_PKG_NAME"))
         .about("An OI Checker. To get more information, please see README.html")
         .version(env!("CARGO_PKG_VERSION"))
-        .arg(make_arg(
-            "tested-program",
-            "tested",
-            't',
-            "FILE",
-            PathBufValueParser::new(),
-            "The program which will be tested.",
-        ))
+        .arg(
+            make_arg(
+                "tested-program",
+                "tested",
+                't',
+                "FILE",
+                PathBufValueParser::new(),
+                "The program which will be tested. Can be used multiple times to compare \
+                several programs.",
+            )
+            .action(ArgAction::Append),
+        )
         .arg(make_arg(
             "accepted-program",
             "accepted",
@@ -39,6 +43,25 @@ pub fn parse_cla() -> ClaConfig {
             PathBufValueParser::new(),
             "The program which generate data.",
         ))
+        .arg(
+            Arg::new("interactor")
+                .long("interactor")
+                .value_name("FILE")
+                .value_parser(PathBufValueParser::new())
+                .help(
+                    "The interactor of an interactive problem. \
+                    An empty string means the problem is not interactive.",
+                ),
+        )
+        .arg(
+            Arg::new("test-data")
+                .long("test-data")
+                .value_name("GLOB")
+                .help(
+                    "Input files of fixed test data, used instead of the data generator. \
+                    See `config_default.toml` for more information.",
+                ),
+        )
         .arg(make_arg(
             "test-cases",
             "cases",
@@ -47,6 +70,63 @@ pub fn parse_cla() -> ClaConfig {
             RangedU64ValueParser::<u32>::new().range(1..),
             "Number of test cases. Each starts a test suite.",
         ))
+        .arg(
+            Arg::new("until-fail")
+                .long("until-fail")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Keep launching new test suites until the first failing one, \
+                    ignoring `test-cases`.",
+                ),
+        )
+        .arg(
+            Arg::new("max-cases")
+                .long("max-cases")
+                .value_name("NUMBER")
+                .value_parser(RangedU64ValueParser::<u32>::new())
+                .help("Maximum number of test cases with `until-fail`. 0 means no limit."),
+        )
+        .arg(
+            Arg::new("time-budget")
+                .long("time-budget")
+                .value_name("SECONDS")
+                .value_parser(RangedU64ValueParser::<u64>::new())
+                .help(
+                    "No new test suite is launched after this duration (in seconds) \
+                    with `until-fail`. 0 means no limit.",
+                ),
+        )
+        .arg(
+            Arg::new("seed")
+                .long("seed")
+                .value_name("NUMBER")
+                .value_parser(RangedU64ValueParser::<u64>::new())
+                .help(
+                    "Base seed of the test cases, available as `{seed}` in launch \
+                    arguments. 0 means a random seed.",
+                ),
+        )
+        .arg(
+            Arg::new("minimize")
+                .long("minimize")
+                .action(ArgAction::SetTrue)
+                .help("Minimize the input of each WA test case."),
+        )
+        .arg(
+            Arg::new("resume")
+                .long("resume")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Skip the test suites recorded in the manifest of the previous run \
+                    if the programs are not changed.",
+                ),
+        )
+        .arg(
+            Arg::new("watch")
+                .long("watch")
+                .action(ArgAction::SetTrue)
+                .help("Rerun all test suites whenever a source file changes."),
+        )
         .arg(make_arg(
             "test-threads",
             "threads",
@@ -64,6 +144,13 @@ pub fn parse_cla() -> ClaConfig {
             "If the tested program doesn't finish in this duration \
             (in milliseconds), the result will be TLE.",
         ))
+        .arg(
+            Arg::new("time-measurement")
+                .long("time-measurement")
+                .value_name("TIME")
+                .value_parser(["cpu", "wall"])
+                .help("Which time of the tested program is compared with `ac-timeout`."),
+        )
         .arg(make_arg(
             "program-timeout",
             "program-timeout",
@@ -74,6 +161,42 @@ pub fn parse_cla() -> ClaConfig {
             (in milliseconds), this suite will be terminated \
             and the result will be Unknown.",
         ))
+        .arg(make_arg(
+            "memory-limit",
+            "memory-limit",
+            'l',
+            "MEGABYTES",
+            RangedU64ValueParser::<u64>::new(),
+            "If the tested program uses more memory than this limit \
+            (in megabytes), the result will be MLE. 0 means no limit.",
+        ))
+        .arg(
+            Arg::new("sandbox")
+                .long("sandbox")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Run the tested programs in a sandbox without network, with a \
+                    read-only filesystem and forbidden system calls (Linux only).",
+                ),
+        )
+        .arg(
+            Arg::new("input-file-name")
+                .long("input-file-name")
+                .value_name("NAME")
+                .help(
+                    "The file which the tested programs read instead of stdin, \
+                    e.g. `problem.in`.",
+                ),
+        )
+        .arg(
+            Arg::new("output-file-name")
+                .long("output-file-name")
+                .value_name("NAME")
+                .help(
+                    "The file which the tested programs write instead of stdout, \
+                    e.g. `problem.out`.",
+                ),
+        )
         .arg(make_arg(
             "working-directory",
             "working-dir",
@@ -82,6 +205,12 @@ pub fn parse_cla() -> ClaConfig {
             PathBufValueParser::new(),
             "The directory which stores data files and compiled files.",
         ))
+        .arg(
+            Arg::new("compile-warnings")
+                .long("compile-warnings")
+                .action(ArgAction::SetTrue)
+                .help("Summarize the warnings of the compilers."),
+        )
         .arg(make_arg(
             "auto-remove-files",
             "auto-remove-files",
@@ -118,11 +247,38 @@ pub fn parse_cla() -> ClaConfig {
                     Split items with ';'",
                 ),
         )
+        .arg(
+            Arg::new("report")
+                .long("report")
+                .value_name("FORMAT=PATH")
+                .action(ArgAction::Append)
+                .value_parser(NonEmptyStringValueParser::new())
+                .help(
+                    "Write a report after the run, e.g. `json=report.json`, `junit` or `html`. \
+                    Can be used multiple times.",
+                ),
+        )
         .arg(
             Arg::new("get-default-config")
                 .long("get-default-config")
                 .action(ArgAction::SetTrue)
                 .help("Print the default config."),
+        )
+        // Options can also be put after the subcommand.
+        .mut_args(|arg| arg.global(true))
+        .subcommand(
+            Command::new("replay")
+                .about(
+                    "Replay a test case of a previous run. The input and accepted output \
+                    in the working directory are reused if they exist.",
+                )
+                .arg(
+                    Arg::new("case")
+                        .value_name("CASE")
+                        .required(true)
+                        .value_parser(NonEmptyStringValueParser::new())
+                        .help("The index of the test case, or its input file (e.g. `data17.in`)."),
+                ),
         );
     let matches = app.get_matches();
     if matches.get_flag("get-default-config") {
@@ -144,46 +300,102 @@ pub fn parse_cla() -> ClaConfig {
         })
     };
 
-    let tested_program = get_one!("tested-program", PathBuf);
+    let tested_program = matches
+        .get_many("tested-program")
+        .map(|values_ref: ValuesRef<PathBuf>| values_ref.cloned().collect());
     let accepted_program = get_one!("accepted-program", PathBuf);
     let data_generator = get_one!("data-generator", PathBuf);
+    let interactor = get_one!("interactor", PathBuf);
+    let test_data = get_one!("test-data", String);
     let test_cases = get_one!("test-cases", u32);
+    let until_fail = matches.get_flag("until-fail").then_some(true);
+    let max_cases = get_one!("max-cases", u32);
+    let time_budget = get_one!("time-budget", u64);
+    let seed = get_one!("seed", u64);
+    let minimize = matches.get_flag("minimize").then_some(true);
+    let resume = matches.get_flag("resume").then_some(true);
+    let watch = matches.get_flag("watch").then_some(true);
     let test_threads = get_one!("test-threads", u32);
     let ac_timeout = get_one!("ac-timeout", u64);
+    let time_measurement = get_one!("time-measurement", String);
     let program_timeout = get_one!("program-timeout", u64);
+    let memory_limit = get_one!("memory-limit", u64);
+    let sandbox = matches.get_flag("sandbox").then_some(true);
+    let input_file_name = get_one!("input-file-name", String);
+    let output_file_name = get_one!("output-file-name", String);
     let working_directory = get_one!("working-directory", PathBuf);
+    let compile_warnings = matches.get_flag("compile-warnings").then_some(true);
     let auto_remove_files = get_one!("auto-remove-files", String);
     let output_filters = get_many_string("output-filters");
     let diff_tool = get_many_string("diff-tool");
+    let report = get_many_string("report");
+    let replay = matches
+        .subcommand_matches("replay")
+        .and_then(|matches| matches.get_one::<String>("case").cloned());
     ClaConfig {
         tested_program,
         accepted_program,
         data_generator,
+        interactor,
+        test_data,
         test_cases,
+        until_fail,
+        max_cases,
+        time_budget,
+        seed,
+        minimize,
+        resume,
+        watch,
         test_threads,
         ac_timeout,
+        time_measurement,
         program_timeout,
+        memory_limit,
+        sandbox,
+        input_file_name,
+        output_file_name,
         working_directory,
+        compile_warnings,
         auto_remove_files,
         output_filters,
         diff_tool,
+        report,
+        replay,
     }
 }
 
 /// Command line arguments configuration.
 #[derive(Debug)]
 pub struct ClaConfig {
-    pub tested_program: Option<PathBuf>,
+    pub tested_program: Option<Vec<PathBuf>>,
     pub accepted_program: Option<PathBuf>,
     pub data_generator: Option<PathBuf>,
+    pub interactor: Option<PathBuf>,
+    pub test_data: Option<String>,
     pub test_cases: Option<u32>,
+    pub until_fail: Option<bool>,
+    pub max_cases: Option<u32>,
+    pub time_budget: Option<u64>,
+    pub seed: Option<u64>,
+    pub minimize: Option<bool>,
+    pub resume: Option<bool>,
+    pub watch: Option<bool>,
     pub test_threads: Option<u32>,
     pub ac_timeout: Option<u64>,
+    pub time_measurement: Option<String>,
     pub program_timeout: Option<u64>,
+    pub memory_limit: Option<u64>,
+    pub sandbox: Option<bool>,
+    pub input_file_name: Option<String>,
+    pub output_file_name: Option<String>,
     pub working_directory: Option<PathBuf>,
+    pub compile_warnings: Option<bool>,
     pub auto_remove_files: Option<String>,
     pub output_filters: Option<Vec<String>>,
     pub diff_tool: Option<Vec<String>>,
+    pub report: Option<Vec<String>>,
+    /// The test case to replay (`replay` subcommand).
+    pub replay: Option<String>,
 }
 
 /// Make an argument for most case to reuse the code.
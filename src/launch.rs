@@ -1,12 +1,27 @@
 //! Main module: Launch the programs.
 
+pub mod comparator;
 pub mod diff_tool;
 pub mod filter;
+mod interactive;
+mod minimize;
+mod process;
+pub mod sandbox;
+pub mod test_data;
+
+pub use process::{interrupt, is_interrupted};
 
 use crate::config::{cf_parsing, dynamic_format};
 use crate::prelude::*;
+use crate::score::Subtask;
+use diff_tool::{DiffTool, DifferenceKind};
+use sandbox::Sandbox;
+use serde::Serialize;
+use std::process::Child;
+use std::sync::Arc;
+use test_data::TestData;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct LaunchConfig {
     pub command: Option<String>,
     pub args: Vec<String>,
@@ -21,27 +36,292 @@ impl From<cf_parsing::LaunchConfig> for LaunchConfig {
     }
 }
 
-impl Default for LaunchConfig {
-    fn default() -> Self {
-        Self {
-            command: None,
-            args: Vec::new(),
+/// Names of the files which a program reads and writes instead of stdin and
+/// stdout, like `freopen("problem.in", "r", stdin)`. A program using any of
+/// them is run in its own directory of the test case.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FileIo {
+    /// The input is copied to the directory with this name.
+    pub input_file_name: Option<String>,
+    /// The output is collected from this file in the directory.
+    pub output_file_name: Option<String>,
+}
+
+impl FileIo {
+    /// Whether the program reads or writes any file.
+    pub fn is_used(&self) -> bool {
+        self.input_file_name.is_some() || self.output_file_name.is_some()
+    }
+}
+
+/// The input and output of a launch.
+pub struct LaunchIo<'a> {
+    /// Piped to stdin (or copied, see `FileIo`). `None` means stdin is
+    /// inherited.
+    pub input_file: Option<&'a PathBuf>,
+    /// The output is saved to this file in all cases.
+    pub output_file: &'a PathBuf,
+    pub file_io: &'a FileIo,
+    /// The directory where the program is run if it uses file I/O. It is
+    /// created before the launch and removed after.
+    pub run_dir: PathBuf,
+}
+
+/// The arguments of launching a program besides the ones in its config.
+#[derive(Debug, Clone, Default)]
+pub struct ExtraArgs {
+    /// The seed of the test case, available as `{seed}` in the config.
+    pub seed: u64,
+    /// Appended after the arguments in the config.
+    pub appended: Vec<String>,
+}
+
+/// Derive the seed of a test case from the base seed (SplitMix64), so that
+/// close indexes get unrelated seeds.
+pub fn get_case_seed(seed: u64, index: u32) -> u64 {
+    let mut z = seed.wrapping_add((index as u64).wrapping_mul(0x9e3779b97f4a7c15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+/// Measured resource usage of a launched program.
+#[derive(Debug, Clone, Copy)]
+pub struct LaunchUsage {
+    /// Wall-clock time.
+    pub duration: Duration,
+    /// User + system CPU time, if the platform is able to measure it.
+    pub cpu_time: Option<Duration>,
+    /// Peak memory in KiB, if the platform is able to measure it.
+    pub peak_memory: Option<u64>,
+}
+
+impl LaunchUsage {
+    /// The time compared with `ac_timeout`. Fall back to wall-clock time if
+    /// CPU time is not measured.
+    pub fn measured_time(&self, time_measurement: TimeMeasurement) -> Duration {
+        match time_measurement {
+            TimeMeasurement::Cpu => self.cpu_time.unwrap_or(self.duration),
+            TimeMeasurement::Wall => self.duration,
+        }
+    }
+}
+
+impl Display for LaunchUsage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let wall_ms = self.duration.as_secs_f64() * 1000.0;
+        match self.cpu_time {
+            Some(cpu_time) => write!(
+                f,
+                "CPU {:.3} ms, wall {:.3} ms",
+                cpu_time.as_secs_f64() * 1000.0,
+                wall_ms
+            ),
+            None => write!(f, "{:.3} ms", wall_ms),
+        }
+    }
+}
+
+/// Which time is compared with `ac_timeout`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeMeasurement {
+    /// User + system CPU time of the program.
+    Cpu,
+    /// Wall-clock time of the program.
+    Wall,
+}
+
+impl TryFrom<&str> for TimeMeasurement {
+    type Error = String;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "cpu" => Ok(Self::Cpu),
+            "wall" => Ok(Self::Wall),
+            s => Err(format!(
+                "`{}` is not allowed in field `time_measurement`",
+                s
+            )),
         }
     }
 }
 
+/// How a program terminated abnormally.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitFailure {
+    /// Exited with a non-zero code.
+    Code(i32),
+    /// Terminated by a signal (Unix only).
+    Signal(i32),
+}
+
+impl ExitFailure {
+    /// Return `None` if the program exited successfully.
+    pub fn from_status(status: std::process::ExitStatus) -> Option<Self> {
+        if status.success() {
+            return None;
+        }
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::ExitStatusExt;
+            if let Some(signal) = status.signal() {
+                return Some(Self::Signal(signal));
+            }
+        }
+        // `code()` is `None` only if terminated by a signal.
+        Some(Self::Code(status.code().unwrap_or(-1)))
+    }
+
+    /// The name of some common fatal signals or Windows exception codes.
+    fn name(&self) -> Option<&'static str> {
+        match *self {
+            #[cfg(unix)]
+            Self::Signal(signal) => match signal {
+                libc::SIGSEGV => Some("SIGSEGV"),
+                libc::SIGFPE => Some("SIGFPE"),
+                libc::SIGABRT => Some("SIGABRT"),
+                libc::SIGILL => Some("SIGILL"),
+                libc::SIGBUS => Some("SIGBUS"),
+                libc::SIGKILL => Some("SIGKILL"),
+                libc::SIGTERM => Some("SIGTERM"),
+                libc::SIGPIPE => Some("SIGPIPE"),
+                libc::SIGSYS => Some("SIGSYS"),
+                libc::SIGXCPU => Some("SIGXCPU"),
+                libc::SIGXFSZ => Some("SIGXFSZ"),
+                _ => None,
+            },
+            #[cfg(windows)]
+            Self::Code(code) => match code as u32 {
+                0xC0000005 => Some("STATUS_ACCESS_VIOLATION"),
+                0xC0000094 => Some("STATUS_INTEGER_DIVIDE_BY_ZERO"),
+                0xC00000FD => Some("STATUS_STACK_OVERFLOW"),
+                0xC0000409 => Some("STATUS_STACK_BUFFER_OVERRUN"),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+impl Display for ExitFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self, self.name()) {
+            (Self::Code(code), Some(name)) => write!(f, "exit code {:#X} {}", code, name),
+            (Self::Code(code), None) => write!(f, "exit code {}", code),
+            (Self::Signal(signal), Some(name)) => write!(f, "signal {} ({})", name, signal),
+            (Self::Signal(signal), None) => write!(f, "signal {}", signal),
+        }
+    }
+}
+
+/// Limits applied to a launched program.
+#[derive(Debug, Clone, Copy)]
+pub struct LaunchLimits<'a> {
+    pub timeout: Duration,
+    /// Memory limit in KiB. `None` means no limit.
+    pub memory_limit: Option<u64>,
+    /// Run the program in the sandbox (see `sandbox`), hiding these paths.
+    /// `None` means no sandbox.
+    pub sandbox: Option<&'a [PathBuf]>,
+}
+
 #[derive(Debug)]
 pub enum LaunchOk {
-    Success(Duration),
-    Timeout(Duration),
+    Success(LaunchUsage),
+    RuntimeError(LaunchUsage, ExitFailure),
+    Timeout(LaunchUsage),
+    MemoryExceeded(LaunchUsage),
+    /// The program finished successfully, but didn't write the output file
+    /// (see `FileIo`).
+    OutputMissing(LaunchUsage),
+}
+
+impl LaunchOk {
+    /// Classify the result of a watched program.
+    fn from_watched(
+        watched: process::Watched,
+        duration: Duration,
+        memory_limit: Option<u64>,
+    ) -> Self {
+        let usage = LaunchUsage {
+            duration,
+            cpu_time: watched.cpu_time,
+            peak_memory: watched.peak_memory,
+        };
+        let memory_exceeded = match (memory_limit, usage.peak_memory) {
+            (Some(limit), Some(peak_memory)) => peak_memory > limit,
+            _ => false,
+        };
+        match watched.status {
+            // The results of an interrupted suite are discarded (see
+            // `SuiteLauncher::run_suite`).
+            Err(process::KillReason::Timeout | process::KillReason::Interrupted) => {
+                Self::Timeout(usage)
+            }
+            Err(process::KillReason::MemoryLimit) => Self::MemoryExceeded(usage),
+            Ok(_) if memory_exceeded => Self::MemoryExceeded(usage),
+            Ok(status) => match ExitFailure::from_status(status) {
+                Some(failure) => Self::RuntimeError(usage, failure),
+                None => Self::Success(usage),
+            },
+        }
+    }
+}
+
+/// A formatted command, ready to be launched.
+struct LaunchCommand {
+    stage: Stage,
+    program: String,
+    args: Vec<String>,
+    /// `None` means the working directory of the checker.
+    current_dir: Option<PathBuf>,
+}
+
+impl LaunchCommand {
+    /// Wrap an IO error into `CheckerError::CommandError`.
+    fn error(&self, err: io::Error) -> Box<CheckerError> {
+        Box::new(CheckerError::CommandError {
+            stage: self.stage,
+            command: self.program.to_owned(),
+            args: self.args.to_owned(),
+            file: self.program.to_owned().into(),
+            msg: format!("Error when launching: {}", err),
+            output: None,
+        })
+    }
+
+    /// Spawn the program in a new process group, in the sandbox if provided.
+    fn spawn(
+        &self,
+        stdin: Stdio,
+        stdout: Stdio,
+        stderr: Stdio,
+        sandbox: Option<&Sandbox>,
+    ) -> CheckerResult<Child> {
+        let mut command = Command::new(&self.program);
+        command
+            .args(&self.args)
+            .stdin(stdin)
+            .stdout(stdout)
+            .stderr(stderr);
+        if let Some(current_dir) = &self.current_dir {
+            command.current_dir(current_dir);
+        }
+        process::set_process_group(&mut command);
+        if let Some(sandbox) = sandbox {
+            sandbox.apply(&mut command).map_err(|err| self.error(err))?;
+        }
+        command.spawn().map_err(|err| self.error(err))
+    }
 }
 
 impl LaunchConfig {
     /// Get the arguments
-    fn get_args(&self, file: &PathBuf, stage: Stage) -> CheckerResult<Vec<String>> {
+    fn get_args(&self, file: &PathBuf, stage: Stage, seed: u64) -> CheckerResult<Vec<String>> {
         // to give the &str longer lifetime
         let s_file = file.to_string_lossy();
-        let args_dict: HashMap<&str, &str> = [("file", &*s_file)].into();
+        let s_seed = seed.to_string();
+        let args_dict: HashMap<&str, &str> = [("file", &*s_file), ("seed", &*s_seed)].into();
         let mut args: Vec<String> = Vec::with_capacity(self.args.len());
         for arg in self.args.iter() {
             args.push(dynamic_format(arg, &args_dict, stage)?);
@@ -49,277 +329,899 @@ impl LaunchConfig {
         Ok(args)
     }
 
-    /// Launch the program. Send messages to the main thread.
-    fn run_inner(
-        mut command: Command,
-        input_file: &Option<PathBuf>,
-        output_file: &PathBuf,
-        tx: mpsc::Sender<Result<(), io::Error>>,
-    ) {
-        use std::io::Write;
-        let mut child = match command.spawn() {
-            Ok(output) => output,
-            Err(e) => {
-                tx.send(Ok(())).ignore();
-                tx.send(Err(e)).ignore();
-                return;
-            }
-        };
-        if let Some(input_file) = input_file {
-            let input_buf = match fs::read(input_file) {
-                Ok(input_buf) => input_buf,
-                Err(e) => {
-                    tx.send(Ok(())).ignore();
-                    tx.send(Err(e)).ignore();
-                    return;
-                }
-            };
-            let input_buf = input_buf.as_slice();
-            let mut child_stdin = child.stdin.take().expect("Stdin not piped");
-            child_stdin.write(input_buf).ignore();
-        }
-        tx.send(Ok(())).ignore();
-        let output = match child.wait_with_output() {
-            Ok(output) => output,
-            Err(e) => {
-                tx.send(Err(e)).ignore();
-                return;
-            }
+    /// Get the command program and arguments.
+    fn get_command(
+        &self,
+        file: &PathBuf,
+        stage: Stage,
+        extra_args: ExtraArgs,
+    ) -> CheckerResult<LaunchCommand> {
+        let mut args = self.get_args(file, stage, extra_args.seed)?;
+        args.extend(extra_args.appended);
+        let program = self
+            .command
+            .clone()
+            .unwrap_or(file.to_string_lossy().to_string());
+        Ok(LaunchCommand {
+            stage,
+            program,
+            args,
+            current_dir: None,
+        })
+    }
+
+    /// Get the command, and create the sandbox of the program if
+    /// `limits.sandbox` is set. The command runs the copy of the program in
+    /// the sandbox, which must be kept until the program exits.
+    fn get_sandboxed_command(
+        &self,
+        file: &PathBuf,
+        stage: Stage,
+        extra_args: ExtraArgs,
+        limits: &LaunchLimits,
+    ) -> CheckerResult<(LaunchCommand, Option<Sandbox>)> {
+        let Some(hidden) = limits.sandbox else {
+            return Ok((self.get_command(file, stage, extra_args)?, None));
         };
-        tx.send(Ok(())).ignore();
-        fs::write(output_file, output.stdout).ignore();
+        let command = self.get_command(file, stage, extra_args.clone())?;
+        let sandbox = Sandbox::new(file, hidden).map_err(|err| command.error(err))?;
+        let command = self.get_command(sandbox.program(), stage, extra_args)?;
+        Ok((command, Some(sandbox)))
     }
 
     /// Run the program.
     ///
+    /// The program is killed (with its whole process group) as soon as it
+    /// breaks `limits`. Its output is saved to `io.output_file` in all cases.
+    /// If it uses file I/O, it is run in `io.run_dir` (or the scratch directory
+    /// of the sandbox), with the input copied to `io.file_io.input_file_name`,
+    /// and the output collected from `io.file_io.output_file_name`.
+    ///
     /// Returned value:
-    /// - Err(_) => Failed to format arguments.
-    /// - Ok(Success(duration)) => Succeed in launching the program. The program
-    ///   finished running in `timeout`.
-    /// - Ok(Timeout(duration)) => Succeed in launching the program, but the
-    ///   program didn't finish running in `timeout`.
+    /// - Err(_) => Failed to format arguments or launch the program.
+    /// - Ok(Success(usage)) => Succeed in launching the program. The program
+    ///   finished running in `limits.timeout`.
+    /// - Ok(RuntimeError(usage, failure)) => Succeed in launching the program,
+    ///   but it exited with a non-zero code or was terminated by a signal.
+    /// - Ok(Timeout(usage)) => Succeed in launching the program, but the
+    ///   program didn't finish running in `limits.timeout`, so it was killed.
+    /// - Ok(MemoryExceeded(usage)) => Succeed in launching the program, but
+    ///   its peak memory exceeded `limits.memory_limit`.
+    /// - Ok(OutputMissing(usage)) => The program finished successfully, but
+    ///   the output file is missing.
     pub fn run(
         &self,
         file: &PathBuf,
         stage: Stage,
-        extra_args: Vec<String>,
-        timeout: Duration,
-        input_file: &Option<PathBuf>,
-        output_file: &PathBuf,
+        extra_args: ExtraArgs,
+        limits: LaunchLimits,
+        io: LaunchIo,
     ) -> CheckerResult<LaunchOk> {
-        let args = {
-            let mut args = self.get_args(file, stage)?;
-            args.extend(extra_args);
-            args
+        use std::io::{Read, Write};
+        let file_io = io.file_io;
+        // The program is run in another directory, so its path must not be
+        // relative.
+        let file = match file_io.is_used() {
+            true => std::path::absolute(file).unwrap_or_else(|_| file.to_owned()),
+            false => file.to_owned(),
         };
-        let program = self
-            .command
-            .clone()
-            .unwrap_or(file.to_string_lossy().to_string());
-        let (tx, rx) = mpsc::channel();
-        let command: Command = {
-            let mut command = Command::new(program.to_owned());
-            command.args(&args).stdout(Stdio::piped());
-            if let Some(_) = input_file {
-                command.stdin(Stdio::piped());
+        let (mut command, sandbox) =
+            self.get_sandboxed_command(&file, stage, extra_args, &limits)?;
+        // The scratch directory is the only writable one in the sandbox.
+        let run_dir = match (file_io.is_used(), &sandbox) {
+            (false, _) => None,
+            (true, Some(sandbox)) => Some(sandbox.scratch_dir().to_owned()),
+            (true, None) => Some(io.run_dir),
+        };
+        if let Some(run_dir) = &run_dir {
+            fs::remove_dir_all(run_dir).ignore();
+            fs::create_dir_all(run_dir).map_err(|err| command.error(err))?;
+            if let (Some(input_file), Some(name)) = (io.input_file, &file_io.input_file_name) {
+                fs::copy(input_file, run_dir.join(name)).map_err(|err| command.error(err))?;
             }
-            command
+            command.current_dir = Some(run_dir.to_owned());
+        }
+
+        let input_buf = match (io.input_file, &file_io.input_file_name) {
+            (Some(input_file), None) => {
+                Some(fs::read(input_file).map_err(|err| command.error(err))?)
+            }
+            _ => None,
         };
-        let _input_file = input_file.to_owned();
-        let _output_file = output_file.to_owned();
-        let handle = thread::spawn(move || {
-            Self::run_inner(command, &_input_file, &_output_file, tx);
-        });
-        rx.recv().ignore();
+        let stdin = match (&input_buf, &file_io.input_file_name) {
+            (Some(_), _) => Stdio::piped(),
+            (None, Some(_)) => Stdio::null(),
+            (None, None) => Stdio::inherit(),
+        };
+        let stdout = match &file_io.output_file_name {
+            Some(_) => Stdio::null(),
+            None => Stdio::piped(),
+        };
+        let mut child = command.spawn(stdin, stdout, Stdio::inherit(), sandbox.as_ref())?;
         let start = Instant::now();
-        let received = rx.recv_timeout(timeout);
+        let stdin_handle = input_buf.map(|input_buf| {
+            let mut child_stdin = child.stdin.take().expect("Stdin not piped");
+            thread::spawn(move || {
+                // The pipe is closed when `child_stdin` is dropped.
+                child_stdin.write_all(&input_buf).ignore();
+            })
+        });
+        let stdout_handle = child.stdout.take().map(|mut child_stdout| {
+            thread::spawn(move || {
+                let mut output_buf = Vec::new();
+                child_stdout.read_to_end(&mut output_buf).ignore();
+                output_buf
+            })
+        });
+
+        let watched = process::watch(
+            &mut child,
+            &process::Limits {
+                deadline: start + limits.timeout,
+                memory_limit: limits.memory_limit,
+            },
+        );
         let duration = start.elapsed();
-        handle.join().ignore();
-        if let Err(_) = received {
-            Ok(LaunchOk::Timeout(duration))
-        } else if let Ok(Err(err)) = received {
-            Err(Box::new(CheckerError::CommandError {
-                stage,
-                command: program.to_owned(),
-                args,
-                file: program.into(),
-                msg: format!("Error when launching: {}", err),
-            }))
-        } else {
-            Ok(LaunchOk::Success(duration))
+        if let Some(stdin_handle) = stdin_handle {
+            stdin_handle.join().ignore();
+        }
+        let mut output_missing = false;
+        if let Some(stdout_handle) = stdout_handle {
+            let output_buf = stdout_handle.join().unwrap_or_default();
+            fs::write(io.output_file, output_buf).ignore();
         }
+        if let Some(run_dir) = &run_dir {
+            if let Some(name) = &file_io.output_file_name {
+                // Don't leave the output of a previous run.
+                if fs::copy(run_dir.join(name), io.output_file).is_err() {
+                    fs::remove_file(io.output_file).ignore();
+                    output_missing = true;
+                }
+            }
+            fs::remove_dir_all(run_dir).ignore();
+        }
+        let watched = watched.map_err(|err| command.error(err))?;
+        Ok(
+            match LaunchOk::from_watched(watched, duration, limits.memory_limit) {
+                LaunchOk::Success(usage) if output_missing => LaunchOk::OutputMissing(usage),
+                result => result,
+            },
+        )
     }
 }
 
 #[derive(Clone)]
 pub struct SuiteLauncher {
     rules: crate::config::ExtensionRules<LaunchConfig>,
+    /// Passed to the data generator and the accepted program. 0 means unknown
+    /// in stress-test mode without `max_cases`.
     test_cases: u32,
     program_timeout: Duration,
     accepted_timeout: Duration,
+    time_measurement: TimeMeasurement,
+    memory_limit: Option<u64>,
     working_directory: PathBuf,
     data_generator: PathBuf,
     accepted_program: PathBuf,
-    tested_program: PathBuf,
+    tested_programs: Vec<PathBuf>,
+    interactor: Option<PathBuf>,
+    test_data: Option<Arc<Vec<TestData>>>,
+    minimize: bool,
     output_filters: Vec<filter::OutputFilter>,
     diff_tool: diff_tool::DiffTool,
+    subtasks: Vec<Subtask>,
+    /// Reuse the existing input instead of running the data generator.
+    reuse_inputs: bool,
+    /// Reuse the existing accepted output instead of running the accepted
+    /// program.
+    reuse_answers: bool,
+    /// Base seed of the test cases.
+    seed: u64,
+    /// Run the tested programs in the sandbox, hiding these paths (the
+    /// working directory, the accepted program and the answers).
+    sandbox: Option<Arc<Vec<PathBuf>>>,
+    file_io: FileIo,
+    accepted_file_io: FileIo,
 }
 
 impl SuiteLauncher {
-    /// Launch a suite. Send messages of launch result.
+    /// Launch a suite. Send a message of launch result for each tested
+    /// program.
+    ///
+    /// Nothing is sent if the checker is interrupted before the suite
+    /// finishes.
     pub fn run_suite(&self, index: u32, tx: mpsc::Sender<LaunchSuiteResult>) {
-        tx.send(LaunchSuiteResult {
-            index,
-            inner: self.run_suite_inner(index),
-        })
-        .expect("Sender should send successfully");
+        if is_interrupted() {
+            return;
+        }
+        let mut results = Vec::new();
+        for (program, inner) in self.run_suite_inner(index).into_iter().enumerate() {
+            let minimized_input = match inner {
+                LaunchSuiteEnum::WA(..) if self.minimize => self.minimize_input(index, program),
+                _ => None,
+            };
+            results.push(LaunchSuiteResult {
+                index,
+                program,
+                inner,
+                tested_output: self.get_files(index, program, "").tp_out,
+                minimized_input,
+            });
+        }
+        // The programs may be killed before finishing, so the suite is
+        // discarded.
+        if is_interrupted() {
+            return;
+        }
+        for result in results {
+            tx.send(result).expect("Sender should send successfully");
+        }
+    }
+
+    /// Get the files of a suite for a tested program. With more than one tested
+    /// program, the output and difference files are tagged with the number of
+    /// the program (starting from 1), e.g. `tested1-2.out`.
+    fn get_files(&self, index: u32, program: usize, suffix: &str) -> SuiteFiles {
+        let tag = match self.tested_programs.len() {
+            1 => String::new(),
+            _ => format!("-{}", program + 1),
+        };
+        SuiteFiles::new(&self.working_directory, index, &tag, suffix)
+    }
+
+    /// Minimize the input of a WA suite while the result of the tested
+    /// program is still WA, and save the smallest failing input to
+    /// `data{n}.min.in`, with the outputs and difference in `ac{n}.min.out`,
+    /// `tested{n}.min.out` and `wa{n}.min.log`. With more than one tested
+    /// program, all of them are tagged like `data{n}-{k}.min.in`.
+    ///
+    /// Return the minimized input file, or `None` if the input can't be
+    /// minimized (interactive problems and provided answer files).
+    fn minimize_input(&self, index: u32, program: usize) -> Option<PathBuf> {
+        if self.interactor.is_some() {
+            return None;
+        }
+        let data_file = match &self.test_data {
+            Some(test_data) => {
+                let test_data = &test_data[index as usize - 1];
+                // The answer doesn't match a modified input.
+                if test_data.answer.is_some() {
+                    return None;
+                }
+                test_data.input.to_owned()
+            }
+            None => self.working_directory.join(format!("data{}.in", index)),
+        };
+        let content = fs::read_to_string(data_file).ok()?;
+        let tag = self.get_files(index, program, "").tag;
+        let files = SuiteFiles::new(&self.working_directory, index, "", &format!("{}.min", tag));
+        let mut fails = |content: &str| {
+            fs::write(&files.data, content).is_ok()
+                && self.prepare_answer(index, &files, None).is_ok()
+                && matches!(self.judge(index, program, &files), LaunchSuiteEnum::WA(..))
+        };
+        let minimized = minimize::minimize(&content, &mut fails);
+        // Run once more so that the kept files belong to the minimized input.
+        if fails(&minimized) {
+            Some(files.data)
+        } else {
+            for file in [&files.data, &files.ac_out, &files.tp_out, &files.log] {
+                fs::remove_file(file).ignore();
+            }
+            None
+        }
+    }
+
+    /// Get the launch rule matching the extension of the program.
+    fn get_launch_rule(&self, program: &PathBuf) -> LaunchConfig {
+        program
+            .extension()
+            .and_then(|ext| self.rules.get_rule(&ext.to_string_lossy().to_string()))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Get the limits of a program. Only the tested program is run under the
+    /// memory limit and in the sandbox.
+    fn get_limits(&self, stage: Stage) -> LaunchLimits<'_> {
+        let tested = matches!(stage, Stage::LaunchTP);
+        LaunchLimits {
+            timeout: self.program_timeout,
+            memory_limit: self.memory_limit.filter(|_| tested),
+            sandbox: self
+                .sandbox
+                .as_deref()
+                .map(Vec::as_slice)
+                .filter(|_| tested),
+        }
+    }
+
+    /// Get the name of the output file of the tested programs.
+    fn get_output_file_name(&self) -> String {
+        self.file_io.output_file_name.clone().unwrap_or_default()
+    }
+
+    /// Get the result of a tested program which terminated abnormally. It is
+    /// a security violation if the program is killed by the seccomp filter of
+    /// the sandbox.
+    fn runtime_error(&self, usage: LaunchUsage, failure: ExitFailure) -> LaunchSuiteEnum {
+        if self.sandbox.is_some() && sandbox::is_violation(&failure) {
+            LaunchSuiteEnum::SV(usage)
+        } else {
+            LaunchSuiteEnum::RE(usage, failure)
+        }
+    }
+
+    /// Get the extra arguments of a program launched in the suite `index`.
+    fn get_extra_args(&self, index: u32, appended: Vec<String>) -> ExtraArgs {
+        ExtraArgs {
+            seed: get_case_seed(self.seed, index),
+            appended,
+        }
     }
-    /// Launch a program.
+
+    /// Launch a program. The tested and accepted programs may use file I/O
+    /// (see `FileIo`), and are run in `case{n}` in the working directory if so.
     ///
     /// Return the same as `crate::launch::LaunchConfig::run`
     fn run_one(
         &self,
         program: &PathBuf,
-        extra_args: Vec<String>,
+        index: u32,
+        appended_args: Vec<String>,
         input_file: &Option<PathBuf>,
         output_file: &PathBuf,
         stage: Stage,
     ) -> CheckerResult<LaunchOk> {
-        let default_launch_rule = LaunchConfig::default();
-        let launch_rule = if let Some(ext) = program.extension() {
-            self.rules
-                .get_rule(&ext.to_string_lossy().to_string())
-                .unwrap_or(&default_launch_rule)
-        } else {
-            &default_launch_rule
+        let no_file_io = FileIo::default();
+        let file_io = match stage {
+            Stage::LaunchTP => &self.file_io,
+            Stage::LaunchAC => &self.accepted_file_io,
+            _ => &no_file_io,
         };
-        launch_rule.run(
+        self.get_launch_rule(program).run(
             program,
             stage,
-            extra_args,
-            self.program_timeout,
-            input_file,
-            output_file,
+            self.get_extra_args(index, appended_args),
+            self.get_limits(stage),
+            LaunchIo {
+                input_file: input_file.as_ref(),
+                output_file,
+                file_io,
+                run_dir: self.working_directory.join(format!("case{}", index)),
+            },
         )
     }
 
+    /// Judge by the time of the tested program after its output is accepted
+    /// (maybe partially).
+    fn judge_time(
+        &self,
+        tp_usage: LaunchUsage,
+        partial: Option<(f64, PathBuf, io::Result<()>)>,
+    ) -> LaunchSuiteEnum {
+        if tp_usage.measured_time(self.time_measurement) > self.accepted_timeout {
+            LaunchSuiteEnum::TLE(tp_usage)
+        } else if let Some((points, log_path, log_result)) = partial {
+            LaunchSuiteEnum::PC(tp_usage, points, log_path, log_result)
+        } else {
+            LaunchSuiteEnum::AC(tp_usage)
+        }
+    }
+
+    /// Launch the tested program cross-wired with the interactor, whose exit
+    /// code decides the result. Called by `self.run_suite_inner` after the
+    /// data is generated.
+    ///
+    /// The interactor is called with `<input> <output>` appended, where
+    /// `<output>` is `tested{n}.out`. Its message is saved to `wa{n}.log`, and
+    /// the transcript of the exchange is saved to `transcript{n}.log` unless
+    /// the result is AC. The files are tagged like the other files of the
+    /// tested program (see `self.get_files`).
+    fn run_interactive(
+        &self,
+        index: u32,
+        program: usize,
+        interactor: &PathBuf,
+        data_file: &PathBuf,
+    ) -> LaunchSuiteEnum {
+        let files = self.get_files(index, program, "");
+        let tp_out_file = files.tp_out;
+        let log_path = files.log;
+        let tested_program = &self.tested_programs[program];
+
+        let limits = self.get_limits(Stage::LaunchTP);
+        let result = self
+            .get_launch_rule(tested_program)
+            .get_sandboxed_command(
+                tested_program,
+                Stage::LaunchTP,
+                self.get_extra_args(index, Vec::new()),
+                &limits,
+            )
+            .and_then(|(tested, sandbox)| {
+                let interactor = self.get_launch_rule(interactor).get_command(
+                    interactor,
+                    Stage::LaunchIT,
+                    self.get_extra_args(
+                        index,
+                        [
+                            data_file.to_string_lossy().to_string(),
+                            tp_out_file.to_string_lossy().to_string(),
+                        ]
+                        .into(),
+                    ),
+                )?;
+                interactive::run(&tested, &interactor, limits, sandbox.as_ref())
+            });
+        let result = match result {
+            Ok(result) => result,
+            Err(err) => {
+                return LaunchSuiteEnum::UK(format!("Launch interactive programs failed: {}", err))
+            }
+        };
+
+        let write_log = || fs::write(&log_path, &result.interactor_message);
+        let verdict =
+            DiffTool::get_checker_verdict(result.interactor_code, &result.interactor_message);
+        // A crash of the tested program usually makes the interactor complain
+        // about an unexpected end of file, so RE outranks the verdict.
+        let suite_result = match (result.tested, verdict) {
+            (LaunchOk::Timeout(usage), _) => LaunchSuiteEnum::TLE(usage),
+            (LaunchOk::MemoryExceeded(usage), _) => LaunchSuiteEnum::MLE(usage),
+            (LaunchOk::OutputMissing(usage), _) => {
+                LaunchSuiteEnum::OutputMissing(usage, self.get_output_file_name())
+            }
+            (LaunchOk::RuntimeError(usage, failure), _) => self.runtime_error(usage, failure),
+            (LaunchOk::Success(_), None) => LaunchSuiteEnum::UK(format!(
+                "Interactor failed: See message in file {}{}",
+                log_path.display(),
+                if write_log().is_ok() {
+                    ""
+                } else {
+                    "[write failed]"
+                }
+            )),
+            (LaunchOk::Success(usage), Some(Some(DifferenceKind::WrongAnswer))) => {
+                LaunchSuiteEnum::WA(usage, log_path.clone(), write_log())
+            }
+            (LaunchOk::Success(usage), Some(Some(DifferenceKind::PresentationError))) => {
+                LaunchSuiteEnum::PE(usage, log_path.clone(), write_log())
+            }
+            (LaunchOk::Success(usage), Some(Some(DifferenceKind::Partial(points)))) => {
+                self.judge_time(usage, Some((points, log_path.clone(), write_log())))
+            }
+            (LaunchOk::Success(usage), Some(None)) => self.judge_time(usage, None),
+        };
+        if !matches!(suite_result, LaunchSuiteEnum::AC(_)) {
+            fs::write(
+                self.working_directory
+                    .join(format!("transcript{}{}.log", index, files.tag)),
+                result.transcript,
+            )
+            .ignore();
+        }
+        suite_result
+    }
+
     /// Launch a suite. Called by `self.run_suite`.
-    fn run_suite_inner(&self, index: u32) -> LaunchSuiteEnum {
-        let work_dir = &self.working_directory;
-        let data_file = work_dir.join(format!("data{}.in", index));
-        let ac_out_file = work_dir.join(format!("ac{}.out", index));
-        let tp_out_file = work_dir.join(format!("tested{}.out", index));
-
-        let dg_result = self.run_one(
-            &self.data_generator,
-            [index.to_string(), self.test_cases.to_string()].into(),
-            &None,
-            &data_file,
-            Stage::LaunchDG,
-        );
-        let dg_handle = dg_result
-            .map(|o| match o {
-                LaunchOk::Success(_) => None,
-                LaunchOk::Timeout(_) => Some("Timeout".into()),
+    ///
+    /// The input and the accepted output are shared by all tested programs.
+    /// Return the result of each tested program.
+    fn run_suite_inner(&self, index: u32) -> Vec<LaunchSuiteEnum> {
+        let programs = 0..self.tested_programs.len();
+        let all_unknown = |hint: String| {
+            programs
+                .clone()
+                .map(|_| LaunchSuiteEnum::UK(hint.clone()))
+                .collect()
+        };
+        let test_data = self
+            .test_data
+            .as_ref()
+            .map(|test_data| &test_data[index as usize - 1]);
+        let files = self.get_files(index, 0, "");
+        let data_file = match test_data {
+            Some(test_data) => test_data.input.to_owned(),
+            None => files.data.to_owned(),
+        };
+
+        if test_data.is_none() && !(self.reuse_inputs && data_file.is_file()) {
+            let mut dg_args = vec![index.to_string(), self.test_cases.to_string()];
+            if !self.subtasks.is_empty() {
+                dg_args.push(Subtask::get_id(&self.subtasks, index).to_string());
+            }
+            let dg_result = self.run_one(
+                &self.data_generator,
+                index,
+                dg_args,
+                &None,
+                &data_file,
+                Stage::LaunchDG,
+            );
+            let dg_handle = dg_result
+                .map(|o| match o {
+                    LaunchOk::Success(_) => None,
+                    LaunchOk::RuntimeError(_, failure) => {
+                        Some(format!("Runtime error ({})", failure))
+                    }
+                    LaunchOk::Timeout(_) => Some("Timeout".into()),
+                    LaunchOk::MemoryExceeded(_) => Some("Memory limit exceeded".into()),
+                    LaunchOk::OutputMissing(_) => Some("Output file missing".into()),
+                })
+                .unwrap_or_else(|err| Some(format!("Inner Error: {}", err)));
+            if let Some(hint) = dg_handle {
+                return all_unknown(format!("Launch data generator failed: {}", hint));
+            }
+        }
+        if let Some(interactor) = &self.interactor {
+            return programs
+                .map(|program| self.run_interactive(index, program, interactor, &data_file))
+                .collect();
+        }
+        let answer = test_data.and_then(|test_data| test_data.answer.as_ref());
+        let files = SuiteFiles {
+            data: data_file,
+            ..files
+        };
+        if !(self.reuse_answers && files.ac_out.is_file()) {
+            if let Err(hint) = self.prepare_answer(index, &files, answer) {
+                return all_unknown(hint);
+            }
+        }
+        programs
+            .map(|program| {
+                let program_files = self.get_files(index, program, "");
+                self.judge(
+                    index,
+                    program,
+                    &SuiteFiles {
+                        data: files.data.clone(),
+                        ..program_files
+                    },
+                )
             })
-            .unwrap_or_else(|err| Some(format!("Inner Error: {}", err)));
-        if let Some(hint) = dg_handle {
-            return LaunchSuiteEnum::UK(format!("Launch data generator failed: {}", hint));
+            .collect()
+    }
+
+    /// Run the accepted program on `files.data` (or copy `answer` if it is
+    /// provided) to `files.ac_out`, and apply the output filters.
+    ///
+    /// Return the hint of the UK result on failure.
+    fn prepare_answer(
+        &self,
+        index: u32,
+        files: &SuiteFiles,
+        answer: Option<&PathBuf>,
+    ) -> Result<(), String> {
+        // The answer file is copied since the output filters modify it in place.
+        if let Some(answer) = answer {
+            if let Err(err) = fs::copy(answer, &files.ac_out) {
+                return Err(format!("Copy answer file failed: {}", err));
+            }
+        } else {
+            let ac_result = self.run_one(
+                &self.accepted_program,
+                index,
+                [index.to_string(), self.test_cases.to_string()].into(),
+                &Some(files.data.clone()),
+                &files.ac_out,
+                Stage::LaunchAC,
+            );
+            let ac_handle = ac_result
+                .map(|o| match o {
+                    LaunchOk::Success(_) => None,
+                    LaunchOk::RuntimeError(_, failure) => {
+                        Some(format!("Runtime error ({})", failure))
+                    }
+                    LaunchOk::Timeout(_) => Some("Timeout".into()),
+                    LaunchOk::MemoryExceeded(_) => Some("Memory limit exceeded".into()),
+                    LaunchOk::OutputMissing(_) => Some("Output file missing".into()),
+                })
+                .unwrap_or_else(|err| Some(format!("Inner Error: {}", err)));
+            if let Some(hint) = ac_handle {
+                return Err(format!("Launch accepted program failed: {}", hint));
+            }
+        }
+        for output_filter in self.output_filters.iter() {
+            if let Err(err) = output_filter.run(&files.ac_out) {
+                return Err(format!("Filter accepted output file failed: {}", err));
+            };
         }
+        Ok(())
+    }
 
+    /// Run a tested program on `files.data`, and judge the output with
+    /// `files.ac_out`. Called by `self.run_suite_inner` after the answer is
+    /// prepared.
+    fn judge(&self, index: u32, program: usize, files: &SuiteFiles) -> LaunchSuiteEnum {
         let tp_result = self.run_one(
-            &self.tested_program,
+            &self.tested_programs[program],
+            index,
             Vec::new(),
-            &Some(data_file.clone()),
-            &tp_out_file,
+            &Some(files.data.clone()),
+            &files.tp_out,
             Stage::LaunchTP,
         );
-        let tp_duration = match tp_result {
-            Ok(LaunchOk::Success(duration) | LaunchOk::Timeout(duration)) => duration,
+        let tp_usage = match tp_result {
+            Ok(LaunchOk::Success(usage)) => usage,
+            Ok(LaunchOk::RuntimeError(usage, failure)) => {
+                return self.runtime_error(usage, failure)
+            }
+            Ok(LaunchOk::Timeout(usage)) => return LaunchSuiteEnum::TLE(usage),
+            Ok(LaunchOk::MemoryExceeded(usage)) => return LaunchSuiteEnum::MLE(usage),
+            Ok(LaunchOk::OutputMissing(usage)) => {
+                return LaunchSuiteEnum::OutputMissing(usage, self.get_output_file_name())
+            }
             Err(err) => {
                 return LaunchSuiteEnum::UK(format!("Launch tested program failed: {}", err))
             }
         };
-        if tp_duration > self.program_timeout {
-            return LaunchSuiteEnum::TLE(tp_duration);
-        }
-
-        let ac_result = self.run_one(
-            &self.accepted_program,
-            [index.to_string(), self.test_cases.to_string()].into(),
-            &Some(data_file.clone()),
-            &ac_out_file,
-            Stage::LaunchAC,
-        );
-        let ac_handle = ac_result
-            .map(|o| match o {
-                LaunchOk::Success(_) => None,
-                LaunchOk::Timeout(_) => Some("Timeout".into()),
-            })
-            .unwrap_or_else(|err| Some(format!("Inner Error: {}", err)));
-        if let Some(hint) = ac_handle {
-            return LaunchSuiteEnum::UK(format!("Launch data generator failed: {}", hint));
-        }
 
         for output_filter in self.output_filters.iter() {
-            if let Err(err) = output_filter.run(&ac_out_file) {
-                return LaunchSuiteEnum::UK(format!("Filter accepted output file failed: {}", err));
-            };
-            if let Err(err) = output_filter.run(&tp_out_file) {
+            if let Err(err) = output_filter.run(&files.tp_out) {
                 return LaunchSuiteEnum::UK(format!("Filter tested output file failed: {}", err));
             };
         }
 
-        let diff_result = self.diff_tool.run(
-            (&tp_out_file, &ac_out_file),
-            &work_dir.join(format!("wa{}.log", index)),
-        );
-        match diff_result {
+        let diff_result =
+            self.diff_tool
+                .run(&files.data, (&files.tp_out, &files.ac_out), &files.log);
+        let partial = match diff_result {
             Ok(diff_ok) => match diff_ok {
                 diff_tool::DiffToolOk::Different {
+                    kind,
                     log_path,
                     log_result,
-                } => return LaunchSuiteEnum::WA(tp_duration, log_path, log_result),
-                diff_tool::DiffToolOk::Same => (),
+                } => match kind {
+                    DifferenceKind::WrongAnswer => {
+                        return LaunchSuiteEnum::WA(tp_usage, log_path, log_result)
+                    }
+                    DifferenceKind::PresentationError => {
+                        return LaunchSuiteEnum::PE(tp_usage, log_path, log_result)
+                    }
+                    DifferenceKind::Partial(points) => Some((points, log_path, log_result)),
+                },
+                diff_tool::DiffToolOk::Failed {
+                    log_path,
+                    log_result,
+                } => {
+                    return LaunchSuiteEnum::UK(format!(
+                        "Checker failed: See message in file {}{}",
+                        log_path.display(),
+                        if log_result.is_ok() {
+                            ""
+                        } else {
+                            "[write failed]"
+                        }
+                    ))
+                }
+                diff_tool::DiffToolOk::Same => None,
             },
             Err(err) => return LaunchSuiteEnum::UK(format!("Different tool failed: {}", err)),
-        }
-        if tp_duration <= self.accepted_timeout {
-            LaunchSuiteEnum::AC(tp_duration)
-        } else {
-            LaunchSuiteEnum::TLE(tp_duration)
+        };
+        self.judge_time(tp_usage, partial)
+    }
+}
+
+/// Paths of the files of a suite in the working directory.
+struct SuiteFiles {
+    data: PathBuf,
+    ac_out: PathBuf,
+    tp_out: PathBuf,
+    log: PathBuf,
+    /// The tag of the tested program.
+    tag: String,
+}
+
+impl SuiteFiles {
+    /// `tag` is inserted into the names of the files of the tested program,
+    /// e.g. `tested1-2.out`, and `suffix` is inserted before the extensions,
+    /// e.g. `data1.min.in`.
+    fn new(work_dir: &PathBuf, index: u32, tag: &str, suffix: &str) -> Self {
+        Self {
+            data: work_dir.join(format!("data{}{}.in", index, suffix)),
+            ac_out: work_dir.join(format!("ac{}{}.out", index, suffix)),
+            tp_out: work_dir.join(format!("tested{}{}{}.out", index, tag, suffix)),
+            log: work_dir.join(format!("wa{}{}{}.log", index, tag, suffix)),
+            tag: tag.to_owned(),
         }
     }
 }
 
-/// The result of launching a suite.
+/// The result of launching a suite for a tested program.
 pub struct LaunchSuiteResult {
     pub index: u32,
+    /// The position of the tested program in `tested_programs`.
+    pub program: usize,
     pub inner: LaunchSuiteEnum,
+    /// The output file of the tested program.
+    pub tested_output: PathBuf,
+    /// The minimized input file of a WA suite, if `minimize` is enabled.
+    pub minimized_input: Option<PathBuf>,
 }
 
 /// The inner enum of `LaunchSuiteEnum`.
 pub enum LaunchSuiteEnum {
-    AC(Duration),
-    WA(Duration, PathBuf, io::Result<()>),
-    TLE(Duration),
+    AC(LaunchUsage),
+    WA(LaunchUsage, PathBuf, io::Result<()>),
+    /// Presentation error reported by the checker.
+    PE(LaunchUsage, PathBuf, io::Result<()>),
+    /// Partially correct reported by the checker, with the points ratio.
+    PC(LaunchUsage, f64, PathBuf, io::Result<()>),
+    TLE(LaunchUsage),
+    /// Memory limit exceeded. The peak memory is recorded in the usage.
+    MLE(LaunchUsage),
+    /// Runtime error, with how the program terminated.
+    RE(LaunchUsage, ExitFailure),
+    /// Security violation: the program made a system call forbidden by the
+    /// sandbox.
+    SV(LaunchUsage),
+    /// The output file (see `FileIo`) is missing, with its name. It is
+    /// counted as WA.
+    OutputMissing(LaunchUsage, String),
     UK(String),
 }
 
+impl LaunchSuiteEnum {
+    /// Short name of the verdict, e.g. `AC`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::AC(_) => "AC",
+            Self::WA(..) | Self::OutputMissing(..) => "WA",
+            Self::PE(..) => "PE",
+            Self::PC(..) => "PC",
+            Self::TLE(_) => "TLE",
+            Self::MLE(_) => "MLE",
+            Self::RE(..) => "RE",
+            Self::SV(_) => "SV",
+            Self::UK(_) => "UK",
+        }
+    }
+
+    /// Resource usage of the tested program. `None` for UK.
+    pub fn usage(&self) -> Option<&LaunchUsage> {
+        match self {
+            Self::AC(usage)
+            | Self::WA(usage, ..)
+            | Self::PE(usage, ..)
+            | Self::PC(usage, ..)
+            | Self::TLE(usage)
+            | Self::MLE(usage)
+            | Self::RE(usage, _)
+            | Self::SV(usage)
+            | Self::OutputMissing(usage, _) => Some(usage),
+            Self::UK(_) => None,
+        }
+    }
+
+    /// The difference or checker message file, if any.
+    pub fn log_path(&self) -> Option<&PathBuf> {
+        match self {
+            Self::WA(_, path, _) | Self::PE(_, path, _) | Self::PC(_, _, path, _) => Some(path),
+            _ => None,
+        }
+    }
+}
+
 impl From<&crate::OIChecker> for SuiteLauncher {
     fn from(value: &crate::OIChecker) -> Self {
         let c = &value.config;
         Self {
             rules: c.launch_rules.to_owned(),
-            test_cases: c.test_cases,
+            test_cases: match c.until_fail {
+                Some(until_fail) => until_fail.max_cases.unwrap_or(0),
+                None => c.test_cases,
+            },
             program_timeout: c.program_timeout,
             accepted_timeout: c.ac_timeout,
+            time_measurement: c.time_measurement,
+            memory_limit: c.memory_limit,
             working_directory: c.working_directory.to_owned(),
             data_generator: c.data_generator.to_owned(),
             accepted_program: c.accepted_program.to_owned(),
-            tested_program: c.tested_program.to_owned(),
+            tested_programs: c.tested_programs.to_owned(),
+            interactor: c.interactor.to_owned(),
+            test_data: c.test_data.to_owned().map(Arc::new),
+            minimize: c.minimize,
             output_filters: c.output_filters.to_owned(),
             diff_tool: c.diff_tool.to_owned(),
+            subtasks: c.subtasks.to_owned(),
+            reuse_inputs: value.reuse_inputs,
+            reuse_answers: value.reuse_answers,
+            seed: c.seed,
+            sandbox: c.sandbox.then(|| {
+                let mut hidden = vec![
+                    c.working_directory.to_owned(),
+                    c.accepted_program.to_owned(),
+                ];
+                if let Some(test_data) = &c.test_data {
+                    hidden.extend(test_data.iter().filter_map(|data| data.answer.to_owned()));
+                }
+                Arc::new(hidden)
+            }),
+            file_io: c.file_io.to_owned(),
+            accepted_file_io: c.accepted_file_io.to_owned(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_case_seed() {
+        assert_eq!(get_case_seed(42, 3), get_case_seed(42, 3));
+        assert_ne!(get_case_seed(42, 3), get_case_seed(42, 4));
+        assert_ne!(get_case_seed(42, 3), get_case_seed(43, 3));
+    }
+
+    #[test]
+    fn test_exit_failure_display() {
+        assert_eq!(ExitFailure::Code(3).to_string(), "exit code 3");
+        #[cfg(unix)]
+        {
+            assert_eq!(
+                ExitFailure::Signal(libc::SIGSEGV).to_string(),
+                format!("signal SIGSEGV ({})", libc::SIGSEGV)
+            );
+            assert_eq!(ExitFailure::Signal(1000).to_string(), "signal 1000");
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_file_io() {
+        let dir = std::env::temp_dir().join(format!("oi_checker_test_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let (input_file, output_file) = (dir.join("data1.in"), dir.join("tested1.out"));
+        fs::write(&input_file, "1 2\n").unwrap();
+        let file_io = FileIo {
+            input_file_name: Some("problem.in".into()),
+            output_file_name: Some("problem.out".into()),
+        };
+        let run = |script: &str| {
+            let config = LaunchConfig {
+                command: Some("sh".into()),
+                args: vec!["-c".into(), script.into()],
+            };
+            let limits = LaunchLimits {
+                timeout: Duration::from_secs(10),
+                memory_limit: None,
+                sandbox: None,
+            };
+            let io = LaunchIo {
+                input_file: Some(&input_file),
+                output_file: &output_file,
+                file_io: &file_io,
+                run_dir: dir.join("case1"),
+            };
+            config.run(&dir, Stage::LaunchTP, ExtraArgs::default(), limits, io)
+        };
+        let result = run("cat problem.in > problem.out");
+        assert!(matches!(result, Ok(LaunchOk::Success(_))));
+        assert_eq!(fs::read_to_string(&output_file).unwrap(), "1 2\n");
+        assert!(!dir.join("case1").exists());
+        let result = run("cat problem.in");
+        assert!(matches!(result, Ok(LaunchOk::OutputMissing(_))));
+        assert!(!output_file.exists());
+        fs::remove_dir_all(&dir).ignore();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_exit_failure_from_status() {
+        use std::os::unix::process::ExitStatusExt;
+        use std::process::ExitStatus;
+        assert_eq!(ExitFailure::from_status(ExitStatus::from_raw(0)), None);
+        assert_eq!(
+            ExitFailure::from_status(ExitStatus::from_raw(2 << 8)),
+            Some(ExitFailure::Code(2))
+        );
+        assert_eq!(
+            ExitFailure::from_status(ExitStatus::from_raw(libc::SIGABRT)),
+            Some(ExitFailure::Signal(libc::SIGABRT))
+        );
+    }
+}
@@ -0,0 +1,325 @@
+//! Built-in comparator of the output files.
+
+use crate::prelude::*;
+use serde::Serialize;
+
+/// Comparison modes of the built-in comparator.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Comparator {
+    /// Compare byte by byte.
+    Exact,
+    /// Compare whitespace-separated tokens.
+    Tokens,
+    /// Compare whitespace-separated tokens. Two numeric tokens are the same if
+    /// either their absolute or relative error is within the epsilon.
+    Numeric { abs_eps: f64, rel_eps: f64 },
+}
+
+/// The first mismatch found by the comparator.
+#[derive(Debug, PartialEq)]
+pub struct Mismatch {
+    /// `(line, column)` in the tested output, starting from 1. `None` means
+    /// the end of the tested output.
+    pub position: Option<(usize, usize)>,
+    /// `None` means the end of the file.
+    pub expected: Option<String>,
+    /// `None` means the end of the file.
+    pub found: Option<String>,
+}
+
+impl Display for Mismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let describe = |token: &Option<String>| match token {
+            Some(token) => format!("`{}`", token),
+            None => "end of file".into(),
+        };
+        match self.position {
+            Some((line, column)) => write!(f, "Line {}, column {}", line, column)?,
+            None => write!(f, "At the end of the output")?,
+        }
+        write!(
+            f,
+            ": expected {}, found {}",
+            describe(&self.expected),
+            describe(&self.found)
+        )
+    }
+}
+
+/// A token with its position.
+struct Token<'a> {
+    text: &'a str,
+    line: usize,
+    column: usize,
+}
+
+/// Split the content into whitespace-separated tokens.
+fn tokenize(content: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    for (line_index, line) in content.lines().enumerate() {
+        let mut start = None;
+        for (i, c) in line.char_indices().chain([(line.len(), ' ')]) {
+            match (c.is_whitespace(), start) {
+                (false, None) => start = Some(i),
+                (true, Some(s)) => {
+                    tokens.push(Token {
+                        text: &line[s..i],
+                        line: line_index + 1,
+                        column: line[..s].chars().count() + 1,
+                    });
+                    start = None;
+                }
+                _ => (),
+            }
+        }
+    }
+    tokens
+}
+
+impl Comparator {
+    /// Compare the tested output with the answer.
+    ///
+    /// Return the first mismatch, or `None` if they are the same.
+    pub fn compare(&self, output: &str, answer: &str) -> Option<Mismatch> {
+        match *self {
+            Self::Exact => Self::compare_exact(output, answer),
+            Self::Tokens => Self::compare_tokens(output, answer, |a, b| a == b),
+            Self::Numeric { abs_eps, rel_eps } => {
+                Self::compare_tokens(output, answer, |found, expected| {
+                    match (found.parse::<f64>(), expected.parse::<f64>()) {
+                        (Ok(found), Ok(expected)) => {
+                            let error = (found - expected).abs();
+                            error <= abs_eps || error <= rel_eps * expected.abs()
+                        }
+                        _ => found == expected,
+                    }
+                })
+            }
+        }
+    }
+
+    fn compare_exact(output: &str, answer: &str) -> Option<Mismatch> {
+        let pos = output
+            .bytes()
+            .zip(answer.bytes())
+            .position(|(a, b)| a != b)
+            .unwrap_or(output.len().min(answer.len()));
+        if pos == output.len() && pos == answer.len() {
+            return None;
+        }
+        // Align to a char boundary of both strings.
+        let pos = (0..=pos)
+            .rev()
+            .find(|&i| output.is_char_boundary(i) && answer.is_char_boundary(i))
+            .unwrap_or(0);
+        let line_start = output[..pos].rfind('\n').map_or(0, |i| i + 1);
+        let line = output[..pos].matches('\n').count() + 1;
+        let column = output[line_start..pos].chars().count() + 1;
+        // The whole line containing the mismatch, `None` at the end of file.
+        let line_at = |content: &str| {
+            content[line_start..]
+                .lines()
+                .next()
+                .map(|line| line.to_owned())
+        };
+        Some(Mismatch {
+            position: Some((line, column)),
+            expected: line_at(answer),
+            found: line_at(output),
+        })
+    }
+
+    fn compare_tokens(
+        output: &str,
+        answer: &str,
+        same: impl Fn(&str, &str) -> bool,
+    ) -> Option<Mismatch> {
+        let found = tokenize(output);
+        let expected = tokenize(answer);
+        for i in 0..found.len().max(expected.len()) {
+            match (found.get(i), expected.get(i)) {
+                (Some(f), Some(e)) if same(f.text, e.text) => (),
+                (f, e) => {
+                    return Some(Mismatch {
+                        position: f.map(|f| (f.line, f.column)),
+                        expected: e.map(|e| e.text.to_owned()),
+                        found: f.map(|f| f.text.to_owned()),
+                    })
+                }
+            }
+        }
+        None
+    }
+
+    /// Compare the files and write the report of the first mismatch to
+    /// `dump_diff_file`.
+    ///
+    /// `files` --- `(tested output, accepted output)`
+    ///
+    /// Returned value:
+    /// - `Err(_)` => Failed to read the files.
+    /// - `Ok(None)` => The files are the same.
+    /// - `Ok(Some(log_result))` => The files are different.
+    pub fn run(
+        &self,
+        files: (&PathBuf, &PathBuf),
+        dump_diff_file: &PathBuf,
+    ) -> io::Result<Option<io::Result<()>>> {
+        let output = fs::read(files.0)?;
+        let answer = fs::read(files.1)?;
+        let mismatch = self.compare(
+            &String::from_utf8_lossy(&output),
+            &String::from_utf8_lossy(&answer),
+        );
+        Ok(mismatch.map(|mismatch| {
+            fs::write(
+                dump_diff_file,
+                format!("{}{}", mismatch, crate::util::LINE_END),
+            )
+        }))
+    }
+}
+
+impl TryFrom<&[String]> for Comparator {
+    type Error = String;
+    fn try_from(value: &[String]) -> Result<Self, Self::Error> {
+        let parse_eps = |i: usize| -> Result<f64, String> {
+            match value.get(i) {
+                None => Ok(1e-6),
+                Some(s) => s
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|eps| *eps >= 0.0)
+                    .ok_or_else(|| {
+                        format!(
+                            "Expected a non-negative number in field `builtin[{}]`, found {}",
+                            i + 1,
+                            s
+                        )
+                    }),
+            }
+        };
+        match value.first().map(|s| s.to_ascii_lowercase()).as_deref() {
+            None | Some("exact") => Ok(Self::Exact),
+            Some("tokens") => Ok(Self::Tokens),
+            Some("numeric") => Ok(Self::Numeric {
+                abs_eps: parse_eps(1)?,
+                rel_eps: parse_eps(2)?,
+            }),
+            Some(mode) => Err(format!("Builtin comparator mode {} is not defined.", mode)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mismatch(
+        position: Option<(usize, usize)>,
+        expected: Option<&str>,
+        found: Option<&str>,
+    ) -> Option<Mismatch> {
+        Some(Mismatch {
+            position,
+            expected: expected.map(|s| s.into()),
+            found: found.map(|s| s.into()),
+        })
+    }
+
+    #[test]
+    fn test_exact() {
+        let c = Comparator::Exact;
+        assert_eq!(c.compare("1 2\n3\n", "1 2\n3\n"), None);
+        assert_eq!(
+            c.compare("1 2\n34\n", "1 2\n35\n"),
+            mismatch(Some((2, 2)), Some("35"), Some("34"))
+        );
+        assert_eq!(
+            c.compare("1 2\n", "1 2\n3\n"),
+            mismatch(Some((2, 1)), Some("3"), None)
+        );
+        assert_eq!(
+            c.compare("1  2", "1 2"),
+            mismatch(Some((1, 3)), Some("1 2"), Some("1  2"))
+        );
+    }
+
+    #[test]
+    fn test_tokens() {
+        let c = Comparator::Tokens;
+        assert_eq!(c.compare("1  2\n\n3 \n", "1 2\n3"), None);
+        assert_eq!(
+            c.compare("1 2\n  4 5", "1 2\n3 5"),
+            mismatch(Some((2, 3)), Some("3"), Some("4"))
+        );
+        assert_eq!(c.compare("1 2", "1 2 3"), mismatch(None, Some("3"), None));
+        assert_eq!(
+            c.compare("1 2 3", "1 2"),
+            mismatch(Some((1, 5)), None, Some("3"))
+        );
+    }
+
+    #[test]
+    fn test_numeric() {
+        let c = Comparator::Numeric {
+            abs_eps: 1e-6,
+            rel_eps: 1e-3,
+        };
+        assert_eq!(c.compare("0.1000001 yes", "0.1 yes"), None);
+        assert_eq!(c.compare("1000.5", "1000"), None);
+        assert_eq!(
+            c.compare("0.11", "0.1"),
+            mismatch(Some((1, 1)), Some("0.1"), Some("0.11"))
+        );
+        assert_eq!(
+            c.compare("1 no", "1 yes"),
+            mismatch(Some((1, 3)), Some("yes"), Some("no"))
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            mismatch(Some((2, 3)), Some("3"), Some("4"))
+                .unwrap()
+                .to_string(),
+            "Line 2, column 3: expected `3`, found `4`"
+        );
+        assert_eq!(
+            mismatch(None, Some("3"), None).unwrap().to_string(),
+            "At the end of the output: expected `3`, found end of file"
+        );
+    }
+
+    #[test]
+    fn test_try_from() {
+        let args = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(Comparator::try_from(&args(&[])[..]), Ok(Comparator::Exact));
+        assert_eq!(
+            Comparator::try_from(&args(&["tokens"])[..]),
+            Ok(Comparator::Tokens)
+        );
+        assert_eq!(
+            Comparator::try_from(&args(&["numeric"])[..]),
+            Ok(Comparator::Numeric {
+                abs_eps: 1e-6,
+                rel_eps: 1e-6
+            })
+        );
+        assert_eq!(
+            Comparator::try_from(&args(&["numeric", "0.01", "0"])[..]),
+            Ok(Comparator::Numeric {
+                abs_eps: 0.01,
+                rel_eps: 0.0
+            })
+        );
+        assert!(Comparator::try_from(&args(&["numeric", "-1"])[..])
+            .unwrap_err()
+            .contains("-1"));
+        assert!(Comparator::try_from(&args(&["lines"])[..])
+            .unwrap_err()
+            .contains("lines"));
+    }
+}
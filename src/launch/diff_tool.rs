@@ -1,9 +1,12 @@
 //! Compare the output files.
 
+use crate::launch::comparator::Comparator;
 use crate::prelude::*;
+use serde::Serialize;
 
 /// Compare output files tool.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum DiffTool {
     /// Use Windows `FC`
     FC(Option<u32>),
@@ -11,68 +14,173 @@ pub enum DiffTool {
     Diff,
     /// Customized diff tool.
     Custom(Vec<String>),
+    /// Testlib-compatible checker (special judge), called with
+    /// `<input> <output> <answer>` appended.
+    Checker(Vec<String>),
+    /// Built-in comparator, without launching any program.
+    Builtin(Comparator),
 }
 
 /// Enum shows that the result of diff tools.
 pub enum DiffToolOk {
     Same,
     Different {
+        kind: DifferenceKind,
+        log_path: PathBuf,
+        log_result: io::Result<()>,
+    },
+    /// The checker failed to judge (testlib `FAIL` or an unknown exit code).
+    Failed {
         log_path: PathBuf,
         log_result: io::Result<()>,
     },
 }
 
+/// How the output differs from the answer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DifferenceKind {
+    WrongAnswer,
+    PresentationError,
+    /// Partially correct, with the points ratio between 0 and 1.
+    Partial(f64),
+}
+
 impl DiffTool {
     /// Return the command program and arguments.
-    fn get_command(&self, files: (&PathBuf, &PathBuf)) -> (String, Vec<String>) {
+    ///
+    /// `files` --- `(tested output, accepted output)`
+    fn get_command(
+        &self,
+        input_file: &PathBuf,
+        files: (&PathBuf, &PathBuf),
+    ) -> (String, Vec<String>) {
         let (program, mut args) = match self {
             Self::FC(Some(n)) => ("fc".into(), [format!("/LB{}", n)].into()),
             Self::FC(_) => ("fc".into(), Vec::new()),
             Self::Diff => ("diff".into(), Vec::new()),
-            Self::Custom(command) => (command[0].to_owned(), command[1..].into()),
+            Self::Custom(command) | Self::Checker(command) => {
+                (command[0].to_owned(), command[1..].into())
+            }
+            Self::Builtin(_) => unreachable!("The built-in comparator runs no command"),
         };
+        if let Self::Checker(_) = self {
+            args.push(input_file.to_string_lossy().to_string());
+        }
         args.push(files.0.to_string_lossy().to_string());
         args.push(files.1.to_string_lossy().to_string());
         (program, args)
     }
 
+    /// Interpret the exit code of a testlib-compatible checker (or
+    /// interactor).
+    ///
+    /// Return `None` if the checker failed.
+    pub fn get_checker_verdict(
+        code: Option<i32>,
+        message: &[u8],
+    ) -> Option<Option<DifferenceKind>> {
+        match code? {
+            0 => Some(None),
+            1 => Some(Some(DifferenceKind::WrongAnswer)),
+            2 => Some(Some(DifferenceKind::PresentationError)),
+            7 => {
+                // testlib `quitp` writes "points <ratio> <message>"
+                let message = String::from_utf8_lossy(message);
+                let mut tokens = message.split_whitespace();
+                let token = match tokens.next()? {
+                    "points" => tokens.next()?,
+                    token => token,
+                };
+                let points = token.parse::<f64>().ok()?;
+                Some(Some(DifferenceKind::Partial(points.clamp(0.0, 1.0))))
+            }
+            _ => None,
+        }
+    }
+
     fn get_result(
+        &self,
         output: io::Result<std::process::Output>,
         dump_diff_file: &PathBuf,
         args: Vec<String>,
         program: String,
     ) -> CheckerResult<DiffToolOk> {
-        match output {
-            Ok(output) if output.status.success() => Ok(DiffToolOk::Same),
-            Ok(output) => {
-                let log_path = dump_diff_file;
-                let log_result = fs::write(&log_path, output.stdout);
-                Ok(DiffToolOk::Different {
-                    log_path: log_path.to_owned(),
-                    log_result,
-                })
-            }
-            Err(err) => Err(Box::new(CheckerError::DiffToolError {
-                command: program,
-                args,
-                err,
-            })),
+        let output = output.map_err(|err| CheckerError::DiffToolError {
+            command: program,
+            args,
+            err,
+        })?;
+        let log_path = dump_diff_file.to_owned();
+        if let Self::Checker(_) = self {
+            // The checker reports its message through stderr.
+            let mut message = output.stderr;
+            message.extend(output.stdout);
+            return Ok(
+                match Self::get_checker_verdict(output.status.code(), &message) {
+                    Some(None) => DiffToolOk::Same,
+                    Some(Some(kind)) => DiffToolOk::Different {
+                        kind,
+                        log_result: fs::write(&log_path, message),
+                        log_path,
+                    },
+                    None => DiffToolOk::Failed {
+                        log_result: fs::write(&log_path, message),
+                        log_path,
+                    },
+                },
+            );
+        }
+        if output.status.success() {
+            Ok(DiffToolOk::Same)
+        } else {
+            Ok(DiffToolOk::Different {
+                kind: DifferenceKind::WrongAnswer,
+                log_result: fs::write(&log_path, output.stdout),
+                log_path,
+            })
         }
     }
 
     /// Run the diff tool.
+    ///
+    /// `files` --- `(tested output, accepted output)`
     pub fn run(
         &self,
+        input_file: &PathBuf,
         files: (&PathBuf, &PathBuf),
         dump_diff_file: &PathBuf,
     ) -> CheckerResult<DiffToolOk> {
-        let (program, args) = self.get_command(files);
+        if let Self::Builtin(comparator) = self {
+            let result = comparator.run(files, dump_diff_file).map_err(|err| {
+                CheckerError::DiffToolError {
+                    command: "builtin".into(),
+                    args: vec![
+                        files.0.to_string_lossy().to_string(),
+                        files.1.to_string_lossy().to_string(),
+                    ],
+                    err,
+                }
+            })?;
+            return Ok(match result {
+                None => DiffToolOk::Same,
+                Some(log_result) => DiffToolOk::Different {
+                    kind: DifferenceKind::WrongAnswer,
+                    log_path: dump_diff_file.to_owned(),
+                    log_result,
+                },
+            });
+        }
+        let (program, args) = self.get_command(input_file, files);
         let output = Command::new(&program)
             .args(&args)
             .stdin(Stdio::null())
             .stdout(Stdio::piped())
+            .stderr(match self {
+                Self::Checker(_) => Stdio::piped(),
+                _ => Stdio::inherit(),
+            })
             .output();
-        Self::get_result(output, dump_diff_file, args, program)
+        self.get_result(output, dump_diff_file, args, program)
     }
 }
 
@@ -91,7 +199,10 @@ impl TryFrom<Vec<String>> for DiffTool {
             },
             "diff" => Ok(DiffTool::Diff),
             "custom" => Ok(DiffTool::Custom(value[1..].to_vec())),
-            r => return Err(format!("Rule {} is not defined.", r)),
+            "checker" if value.len() >= 2 => Ok(DiffTool::Checker(value[1..].to_vec())),
+            "checker" => Err("Expected the checker program in field `checker[1]`".into()),
+            "builtin" => Ok(DiffTool::Builtin(value[1..].try_into()?)),
+            r => Err(format!("Rule {} is not defined.", r)),
         }
     }
 }
@@ -99,25 +210,55 @@ impl TryFrom<Vec<String>> for DiffTool {
 #[cfg(test)]
 mod tests {
     use super::*;
-    fn as_vec_str<'a>(v: &'a Vec<String>) -> Vec<&'a str> {
+    fn as_vec_str(v: &Vec<String>) -> Vec<&str> {
         v.iter().map(|s| s.as_str()).collect()
     }
 
     #[test]
     fn test_get_command() {
-        let (p1, a1) = DiffTool::FC(None).get_command((&"1.txt".into(), &"2.txt".into()));
+        let input = "in".into();
+        let (p1, a1) = DiffTool::FC(None).get_command(&input, (&"1.txt".into(), &"2.txt".into()));
         assert_eq!(p1.as_str(), "fc");
         assert_eq!(as_vec_str(&a1), vec!["1.txt", "2.txt"]);
-        let (p2, a2) = DiffTool::FC(Some(3)).get_command((&"a".into(), &"b".into()));
+        let (p2, a2) = DiffTool::FC(Some(3)).get_command(&input, (&"a".into(), &"b".into()));
         assert_eq!(p2.as_str(), "fc");
         assert_eq!(as_vec_str(&a2), vec!["/LB3", "a", "b"]);
-        let (p2, a2) = DiffTool::Diff.get_command((&"a".into(), &"b".into()));
+        let (p2, a2) = DiffTool::Diff.get_command(&input, (&"a".into(), &"b".into()));
         assert_eq!(p2.as_str(), "diff");
         assert_eq!(as_vec_str(&a2), vec!["a", "b"]);
         let (p4, a4) = DiffTool::Custom(vec!["my-diff".into(), "--special".into()])
-            .get_command((&"a".into(), &"b".into()));
+            .get_command(&input, (&"a".into(), &"b".into()));
         assert_eq!(p4.as_str(), "my-diff");
         assert_eq!(as_vec_str(&a4), vec!["--special", "a", "b"]);
+        let (p5, a5) =
+            DiffTool::Checker(vec!["chk".into()]).get_command(&input, (&"a".into(), &"b".into()));
+        assert_eq!(p5.as_str(), "chk");
+        assert_eq!(as_vec_str(&a5), vec!["in", "a", "b"]);
+    }
+
+    #[test]
+    fn test_get_checker_verdict() {
+        use DifferenceKind::*;
+        assert_eq!(DiffTool::get_checker_verdict(Some(0), b"ok"), Some(None));
+        assert_eq!(
+            DiffTool::get_checker_verdict(Some(1), b"wrong answer"),
+            Some(Some(WrongAnswer))
+        );
+        assert_eq!(
+            DiffTool::get_checker_verdict(Some(2), b""),
+            Some(Some(PresentationError))
+        );
+        assert_eq!(DiffTool::get_checker_verdict(Some(3), b"fail"), None);
+        assert_eq!(DiffTool::get_checker_verdict(None, b""), None);
+        assert_eq!(
+            DiffTool::get_checker_verdict(Some(7), b"points 0.25 almost"),
+            Some(Some(Partial(0.25)))
+        );
+        assert_eq!(
+            DiffTool::get_checker_verdict(Some(7), b"0.5"),
+            Some(Some(Partial(0.5)))
+        );
+        assert_eq!(DiffTool::get_checker_verdict(Some(7), b"points"), None);
     }
 
     #[test]
@@ -155,6 +296,28 @@ mod tests {
             ),
             format!("{:?}", DiffTool::Diff)
         );
+        assert_eq!(
+            format!(
+                "{:?}",
+                DiffTool::try_from(Vec::from(["builtin".into(), "tokens".into()])).unwrap()
+            ),
+            format!("{:?}", DiffTool::Builtin(Comparator::Tokens))
+        );
+        assert!(
+            DiffTool::try_from(Vec::from(["builtin".into(), "none".into()]))
+                .unwrap_err()
+                .contains("none")
+        );
+        assert!(DiffTool::try_from(Vec::from(["checker".into()]))
+            .unwrap_err()
+            .contains("checker"));
+        assert_eq!(
+            format!(
+                "{:?}",
+                DiffTool::try_from(Vec::from(["checker".into(), "chk".into()])).unwrap()
+            ),
+            format!("{:?}", DiffTool::Checker(["chk".into()].into()))
+        );
         assert_eq!(
             format!(
                 "{:?}",
@@ -1,10 +1,12 @@
 //! Filter output.
 
 use crate::prelude::*;
+use serde::Serialize;
 use std::borrow::Cow;
 use std::io::Write;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum OutputFilter {
     StripTrailingWhitespace,
     StripTrailingEmptyLines,
@@ -0,0 +1,151 @@
+//! Interactive problems: cross-wire the tested program with an interactor.
+
+use super::{process, LaunchCommand, LaunchLimits, LaunchOk, Sandbox};
+use crate::prelude::*;
+use std::io::{Read, Write};
+use std::sync::{Arc, Mutex};
+
+/// The result of an interactive run.
+pub struct InteractiveOk {
+    pub tested: LaunchOk,
+    /// Exit code of the interactor. `None` if it was killed or terminated by
+    /// a signal.
+    pub interactor_code: Option<i32>,
+    /// What the interactor wrote to stderr.
+    pub interactor_message: Vec<u8>,
+    /// The exchange between the two programs, one line per message.
+    pub transcript: Vec<u8>,
+}
+
+/// Copy everything from `from` to `to`, recording each line into the
+/// transcript with `prefix`. `to` is closed when `from` reaches its end.
+fn relay(
+    mut from: impl Read + Send + 'static,
+    to: impl Write + Send + 'static,
+    prefix: &'static str,
+    transcript: Arc<Mutex<Vec<u8>>>,
+) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        let mut to = Some(to);
+        let mut buf = [0u8; 4096];
+        let mut line = Vec::new();
+        let record = |line: &mut Vec<u8>| {
+            let mut transcript = transcript.lock().expect("Transcript lock poisoned");
+            transcript.extend_from_slice(prefix.as_bytes());
+            transcript.append(line);
+            if transcript.last() != Some(&b'\n') {
+                transcript.push(b'\n');
+            }
+        };
+        loop {
+            let n = match from.read(&mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => n,
+            };
+            if let Some(writer) = to.as_mut() {
+                // Keep draining `from` after the receiver is gone, so that the
+                // sender never blocks on a full pipe.
+                if writer
+                    .write_all(&buf[..n])
+                    .and_then(|_| writer.flush())
+                    .is_err()
+                {
+                    to = None;
+                }
+            }
+            for &byte in &buf[..n] {
+                line.push(byte);
+                if byte == b'\n' {
+                    record(&mut line);
+                }
+            }
+        }
+        if !line.is_empty() {
+            record(&mut line);
+        }
+    })
+}
+
+/// Run the tested program with its stdin and stdout cross-wired with the
+/// interactor. Both programs are killed when `limits.timeout` passes, and
+/// only the tested program is limited by `limits.memory_limit`, and run in
+/// `sandbox` if provided (see `LaunchConfig::get_sandboxed_command`).
+///
+/// Error when failing to launch or wait for either program.
+pub(super) fn run(
+    tested: &LaunchCommand,
+    interactor: &LaunchCommand,
+    limits: LaunchLimits,
+    sandbox: Option<&Sandbox>,
+) -> CheckerResult<InteractiveOk> {
+    let mut tested_child =
+        tested.spawn(Stdio::piped(), Stdio::piped(), Stdio::inherit(), sandbox)?;
+    let mut interactor_child =
+        match interactor.spawn(Stdio::piped(), Stdio::piped(), Stdio::piped(), None) {
+            Ok(child) => child,
+            Err(err) => {
+                process::kill(&mut tested_child);
+                tested_child.wait().ignore();
+                return Err(err);
+            }
+        };
+    let start = Instant::now();
+    let transcript = Arc::new(Mutex::new(Vec::new()));
+    let relay_handles = [
+        relay(
+            tested_child.stdout.take().expect("Stdout not piped"),
+            interactor_child.stdin.take().expect("Stdin not piped"),
+            "[tested] ",
+            transcript.clone(),
+        ),
+        relay(
+            interactor_child.stdout.take().expect("Stdout not piped"),
+            tested_child.stdin.take().expect("Stdin not piped"),
+            "[interactor] ",
+            transcript.clone(),
+        ),
+    ];
+    let mut interactor_stderr = interactor_child.stderr.take().expect("Stderr not piped");
+    let message_handle = thread::spawn(move || {
+        let mut message = Vec::new();
+        interactor_stderr.read_to_end(&mut message).ignore();
+        message
+    });
+
+    let deadline = start + limits.timeout;
+    let interactor_handle = thread::spawn(move || {
+        let limits = process::Limits {
+            deadline,
+            memory_limit: None,
+        };
+        process::watch(&mut interactor_child, &limits)
+    });
+    let tested_watched = process::watch(
+        &mut tested_child,
+        &process::Limits {
+            deadline,
+            memory_limit: limits.memory_limit,
+        },
+    );
+    let duration = start.elapsed();
+    let interactor_watched = interactor_handle
+        .join()
+        .expect("Interactor watching thread panicked");
+    for handle in relay_handles {
+        handle.join().ignore();
+    }
+    let interactor_message = message_handle.join().unwrap_or_default();
+
+    let tested_watched = tested_watched.map_err(|err| tested.error(err))?;
+    let interactor_watched = interactor_watched.map_err(|err| interactor.error(err))?;
+    let transcript = std::mem::take(&mut *transcript.lock().expect("Transcript lock poisoned"));
+    Ok(InteractiveOk {
+        tested: LaunchOk::from_watched(tested_watched, duration, limits.memory_limit),
+        interactor_code: interactor_watched
+            .status
+            .ok()
+            .and_then(|status| status.code()),
+        interactor_message,
+        transcript,
+    })
+}
@@ -0,0 +1,109 @@
+//! Minimize failing inputs by delta debugging.
+
+/// Maximum number of candidates tried when minimizing one input.
+pub const MAX_ATTEMPTS: u32 = 500;
+
+/// Remove as many items as possible while `fails` still holds, by trying to
+/// remove chunks of decreasing size (delta debugging).
+///
+/// `fails` is called at most `attempts` times in total, and the counter is
+/// shared between calls so that several passes respect one budget.
+pub fn ddmin<T: Clone>(
+    mut items: Vec<T>,
+    attempts: &mut u32,
+    mut fails: impl FnMut(&[T]) -> bool,
+) -> Vec<T> {
+    let mut granularity = 2;
+    while items.len() >= 2 {
+        let chunk = items.len().div_ceil(granularity);
+        let mut reduced = false;
+        let mut start = 0;
+        while start < items.len() {
+            if *attempts == 0 {
+                return items;
+            }
+            *attempts -= 1;
+            let end = (start + chunk).min(items.len());
+            let candidate = [&items[..start], &items[end..]].concat();
+            if fails(&candidate) {
+                items = candidate;
+                granularity = (granularity - 1).max(2);
+                reduced = true;
+                break;
+            }
+            start = end;
+        }
+        if !reduced {
+            if granularity >= items.len() {
+                break;
+            }
+            granularity = (granularity * 2).min(items.len());
+        }
+    }
+    items
+}
+
+/// Minimize the content first by lines, then by whitespace-separated tokens
+/// (keeping the tokens left in each line on the same line).
+///
+/// `fails` tells if the candidate content still fails.
+pub fn minimize(content: &str, mut fails: impl FnMut(&str) -> bool) -> String {
+    let mut attempts = MAX_ATTEMPTS;
+    let join_lines =
+        |lines: &[&str]| -> String { lines.iter().map(|line| format!("{}\n", line)).collect() };
+
+    let lines = ddmin(content.lines().collect(), &mut attempts, |lines| {
+        fails(&join_lines(lines))
+    });
+
+    let tokens: Vec<(usize, &str)> = lines
+        .iter()
+        .enumerate()
+        .flat_map(|(i, line)| line.split_whitespace().map(move |token| (i, token)))
+        .collect();
+    let join_tokens = |tokens: &[(usize, &str)]| {
+        let mut content = String::new();
+        for (i, (line, token)) in tokens.iter().enumerate() {
+            content.push_str(token);
+            match tokens.get(i + 1) {
+                Some((next_line, _)) if next_line == line => content.push(' '),
+                _ => content.push('\n'),
+            }
+        }
+        content
+    };
+    let tokens = ddmin(tokens, &mut attempts, |tokens| fails(&join_tokens(tokens)));
+    join_tokens(&tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ddmin() {
+        let mut attempts = MAX_ATTEMPTS;
+        let items: Vec<u32> = (1..=100).collect();
+        let result = ddmin(items, &mut attempts, |items| {
+            items.contains(&37) && items.contains(&81)
+        });
+        assert_eq!(result, [37, 81]);
+
+        let mut attempts = 0;
+        let result = ddmin(vec![1, 2, 3], &mut attempts, |_| true);
+        assert_eq!(result, [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_minimize() {
+        let content = "3\n1 2 3\n4 5 6\n7 8 9\n";
+        let result = minimize(content, |content| content.contains('5'));
+        assert_eq!(result, "5\n");
+        let result = minimize(content, |content| {
+            content
+                .lines()
+                .any(|line| line.contains('4') && line.contains('6'))
+        });
+        assert_eq!(result, "4 6\n");
+    }
+}
@@ -0,0 +1,190 @@
+//! Spawn child processes and watch them with a deadline.
+
+use crate::prelude::*;
+use std::process::{Child, ExitStatus};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Interval between two checks of a running child.
+const POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+/// Set when the checker is interrupted. All watched children are killed.
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Interrupt the checker: kill all watched children (within a poll interval)
+/// and stop launching new ones.
+pub fn interrupt() {
+    INTERRUPTED.store(true, Ordering::SeqCst);
+}
+
+/// Whether the checker is interrupted.
+pub fn is_interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
+
+/// Limits applied while watching a child.
+#[derive(Debug, Clone, Copy)]
+pub struct Limits {
+    pub deadline: Instant,
+    /// Maximum resident set size in KiB. `None` means no limit.
+    pub memory_limit: Option<u64>,
+}
+
+/// Why the child was killed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KillReason {
+    Timeout,
+    MemoryLimit,
+    /// The checker is interrupted (see `interrupt`).
+    Interrupted,
+}
+
+/// The result of watching a child until it exits.
+#[derive(Debug)]
+pub struct Watched {
+    /// `Ok(status)` if the child exited by itself, `Err(reason)` if it was killed.
+    pub status: Result<ExitStatus, KillReason>,
+    /// Peak resident set size in KiB, if the platform is able to measure it.
+    pub peak_memory: Option<u64>,
+    /// User + system CPU time, if the platform is able to measure it.
+    pub cpu_time: Option<Duration>,
+}
+
+/// Put the command into a new process group, so that the whole group can be
+/// killed when the deadline passes.
+pub fn set_process_group(command: &mut Command) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = command;
+    }
+}
+
+/// Kill the child and (on Unix) its whole process group.
+pub fn kill(child: &mut Child) {
+    #[cfg(unix)]
+    {
+        // SAFETY: `kill` has no memory-safety preconditions. The child is the
+        // leader of its own process group (see `set_process_group`).
+        unsafe {
+            libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL);
+        }
+    }
+    child.kill().ignore();
+}
+
+/// Watch the child until it exits, or kill it when it breaks `limits`.
+///
+/// Error when failing to wait for the child.
+#[cfg(unix)]
+pub fn watch(child: &mut Child, limits: &Limits) -> io::Result<Watched> {
+    use std::os::unix::process::ExitStatusExt;
+
+    let pid = child.id() as libc::pid_t;
+    let mut killed = None;
+    loop {
+        let mut status: libc::c_int = 0;
+        // SAFETY: an all-zero `rusage` is a valid value.
+        let mut rusage: libc::rusage = unsafe { std::mem::zeroed() };
+        let flags = if killed.is_some() { 0 } else { libc::WNOHANG };
+        // SAFETY: `status` and `rusage` are valid for writes. `pid` is our
+        // child which hasn't been reaped yet.
+        let ret = unsafe { libc::wait4(pid, &mut status, flags, &mut rusage) };
+        if ret == pid {
+            let peak_memory = if cfg!(target_os = "macos") {
+                rusage.ru_maxrss as u64 / 1024
+            } else {
+                rusage.ru_maxrss as u64
+            };
+            let timeval_to_duration = |t: libc::timeval| {
+                Duration::from_secs(t.tv_sec as u64) + Duration::from_micros(t.tv_usec as u64)
+            };
+            let cpu_time =
+                timeval_to_duration(rusage.ru_utime) + timeval_to_duration(rusage.ru_stime);
+            return Ok(Watched {
+                status: killed.map_or(Ok(ExitStatus::from_raw(status)), Err),
+                peak_memory: Some(peak_memory),
+                cpu_time: Some(cpu_time),
+            });
+        } else if ret == -1 {
+            let err = io::Error::last_os_error();
+            if err.kind() != io::ErrorKind::Interrupted {
+                return Err(err);
+            }
+            continue;
+        }
+
+        let now = Instant::now();
+        if is_interrupted() {
+            killed = Some(KillReason::Interrupted);
+        } else if now >= limits.deadline {
+            killed = Some(KillReason::Timeout);
+        } else if limits
+            .memory_limit
+            .is_some_and(|limit| current_memory(child.id()).is_some_and(|rss| rss > limit))
+        {
+            killed = Some(KillReason::MemoryLimit);
+        }
+        if killed.is_some() {
+            kill(child);
+        } else {
+            thread::sleep(POLL_INTERVAL.min(limits.deadline - now));
+        }
+    }
+}
+
+/// Watch the child until it exits, or kill it when it breaks `limits`.
+///
+/// Only the deadline is enforced on this platform.
+///
+/// Error when failing to wait for the child.
+#[cfg(not(unix))]
+pub fn watch(child: &mut Child, limits: &Limits) -> io::Result<Watched> {
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Watched {
+                status: Ok(status),
+                peak_memory: None,
+                cpu_time: None,
+            });
+        }
+        let now = Instant::now();
+        let killed = if is_interrupted() {
+            Some(KillReason::Interrupted)
+        } else if now >= limits.deadline {
+            Some(KillReason::Timeout)
+        } else {
+            None
+        };
+        if let Some(reason) = killed {
+            kill(child);
+            child.wait()?;
+            return Ok(Watched {
+                status: Err(reason),
+                peak_memory: None,
+                cpu_time: None,
+            });
+        }
+        thread::sleep(POLL_INTERVAL.min(limits.deadline - now));
+    }
+}
+
+/// Current resident set size of a running process in KiB.
+#[cfg(target_os = "linux")]
+fn current_memory(pid: u32) -> Option<u64> {
+    let status = fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    line.split_whitespace().nth(1)?.parse().ok()
+}
+
+/// Current resident set size of a running process in KiB.
+///
+/// Not available on this platform: the limit is only checked with the peak
+/// memory after the process exits.
+#[cfg(all(unix, not(target_os = "linux")))]
+fn current_memory(_pid: u32) -> Option<u64> {
+    None
+}
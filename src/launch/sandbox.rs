@@ -0,0 +1,531 @@
+//! An optional sandbox of the tested program (Linux only).
+//!
+//! The program is run in new user, mount, PID, network, IPC and UTS
+//! namespaces. It has no network, and it can only see and signal the
+//! processes of its own sandbox. Its PID namespace gets a fresh `/proc`, so
+//! the other processes of the host can't be reached through it either.
+//!
+//! The whole filesystem is read-only except a scratch directory created for
+//! each launch. The working directory of the checker (with the accepted
+//! outputs), the accepted program and the answer files of the test data are
+//! hidden, so the program is copied into the sandbox before it is run. Other
+//! files stay readable.
+//!
+//! The numbers of open files and processes are limited, and a seccomp filter
+//! kills the program (with `SIGSYS`) on any dangerous system call, which is
+//! reported as a security violation.
+
+use super::ExitFailure;
+use crate::prelude::*;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// Whether the sandbox is supported on this platform.
+pub const SUPPORTED: bool = cfg!(all(
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+));
+
+/// Maximum number of open files of the sandboxed program.
+const MAX_OPEN_FILES: u64 = 64;
+/// Maximum number of processes (and threads) of the sandboxed program.
+const MAX_PROCESSES: u64 = 16;
+
+/// Number of the created sandboxes, to name the next one.
+static SANDBOX_COUNT: AtomicU32 = AtomicU32::new(0);
+
+/// The sandbox of a launch. Its files are removed when dropped.
+pub struct Sandbox {
+    /// The directory of the files of the sandbox, in the temporary directory.
+    root: PathBuf,
+    /// The copy of the program, which is read-only in the sandbox.
+    program: PathBuf,
+    scratch_dir: PathBuf,
+    /// An empty file mounted over the hidden files.
+    empty_file: PathBuf,
+    /// The existing hidden paths, and whether each of them is a directory.
+    hidden: Vec<(PathBuf, bool)>,
+}
+
+impl Sandbox {
+    /// Create the sandbox of `program` with a new scratch directory, hiding
+    /// `hidden` (the paths which don't exist are skipped).
+    pub fn new(program: &PathBuf, hidden: &[PathBuf]) -> io::Result<Self> {
+        let root = std::env::temp_dir().join(format!(
+            "oi_checker_sandbox_{}_{}",
+            std::process::id(),
+            SANDBOX_COUNT.fetch_add(1, Ordering::SeqCst)
+        ));
+        fs::create_dir_all(root.join("program"))?;
+        let root = root.canonicalize()?;
+        let sandbox = Self {
+            program: root
+                .join("program")
+                .join(program.file_name().unwrap_or_default()),
+            scratch_dir: root.join("scratch"),
+            empty_file: root.join("empty"),
+            hidden: hidden
+                .iter()
+                .filter_map(|path| path.canonicalize().ok())
+                .map(|path| {
+                    let is_dir = path.is_dir();
+                    (path, is_dir)
+                })
+                .collect(),
+            root,
+        };
+        // Cleaned up by `drop` on failure.
+        fs::create_dir(&sandbox.scratch_dir)?;
+        File::create(&sandbox.empty_file)?;
+        fs::copy(program, &sandbox.program)?;
+        Ok(sandbox)
+    }
+
+    /// The copy of the program to be run.
+    pub fn program(&self) -> &PathBuf {
+        &self.program
+    }
+
+    /// The scratch directory, the only writable directory in the sandbox.
+    pub fn scratch_dir(&self) -> &PathBuf {
+        &self.scratch_dir
+    }
+
+    /// Run the command in the sandbox. The scratch directory is also passed
+    /// as `TMPDIR`.
+    ///
+    /// The spawned process enters the namespaces, and supervises an init of
+    /// the new PID namespace, which runs the program and reaps the orphans.
+    /// The spawned process exits (or is killed) like the program, so it can
+    /// be watched as the program. All of the processes are in the same
+    /// process group.
+    ///
+    /// Error when the sandbox is not supported on this platform. Errors while
+    /// entering the sandbox are returned when the command is spawned.
+    #[cfg(all(
+        target_os = "linux",
+        any(target_arch = "x86_64", target_arch = "aarch64")
+    ))]
+    pub fn apply(&self, command: &mut Command) -> io::Result<()> {
+        use std::ffi::CString;
+        use std::os::unix::ffi::OsStrExt;
+        use std::os::unix::process::CommandExt;
+
+        let to_c_string = |bytes: &[u8]| {
+            CString::new(bytes).map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))
+        };
+        let path_to_c_string = |path: &PathBuf| to_c_string(path.as_os_str().as_bytes());
+        // Everything is prepared before forking, since allocating in the
+        // child of a multi-threaded process is unsafe.
+        let scratch_dir = path_to_c_string(&self.scratch_dir)?;
+        let empty_file = path_to_c_string(&self.empty_file)?;
+        let mut hidden = Vec::with_capacity(self.hidden.len());
+        for (path, is_dir) in self.hidden.iter() {
+            hidden.push((path_to_c_string(path)?, *is_dir));
+        }
+        let root = to_c_string(b"/")?;
+        let proc = to_c_string(b"/proc")?;
+        let proc_type = to_c_string(b"proc")?;
+        let tmpfs_type = to_c_string(b"tmpfs")?;
+        let setgroups = to_c_string(b"/proc/self/setgroups")?;
+        let uid_map_file = to_c_string(b"/proc/self/uid_map")?;
+        let gid_map_file = to_c_string(b"/proc/self/gid_map")?;
+        let current_dir = match command.get_current_dir() {
+            Some(dir) => Some(to_c_string(dir.as_os_str().as_bytes())?),
+            None => None,
+        };
+        // SAFETY: `getuid` and `getgid` are always successful.
+        let (uid, gid) = unsafe { (libc::getuid(), libc::getgid()) };
+        // The same user is mapped, so the owners of the files are unchanged.
+        let uid_map = format!("{0} {0} 1", uid).into_bytes();
+        let gid_map = format!("{0} {0} 1", gid).into_bytes();
+        let mut filter = seccomp_filter();
+
+        command.env("TMPDIR", &self.scratch_dir);
+        // SAFETY: only async-signal-safe system calls are made in the child,
+        // with the data prepared above.
+        unsafe {
+            command.pre_exec(move || {
+                let check = |ret: libc::c_long| match ret {
+                    -1 => Err(io::Error::last_os_error()),
+                    _ => Ok(()),
+                };
+                let write_file = |path: &CString, content: &[u8]| {
+                    let fd = libc::open(path.as_ptr(), libc::O_WRONLY | libc::O_CLOEXEC);
+                    check(fd as libc::c_long)?;
+                    let written = libc::write(fd, content.as_ptr().cast(), content.len());
+                    libc::close(fd);
+                    check(written as libc::c_long)
+                };
+                let mount = |source: *const libc::c_char,
+                             target: &CString,
+                             fs_type: *const libc::c_char,
+                             flags: libc::c_ulong| {
+                    check(
+                        libc::mount(source, target.as_ptr(), fs_type, flags, std::ptr::null())
+                            as libc::c_long,
+                    )
+                };
+                let mount_setattr = |path: &CString, flags: libc::c_uint, attr: &MountAttr| {
+                    check(libc::syscall(
+                        libc::SYS_mount_setattr,
+                        libc::AT_FDCWD,
+                        path.as_ptr(),
+                        flags,
+                        attr as *const MountAttr,
+                        std::mem::size_of::<MountAttr>(),
+                    ))
+                };
+                let set_rlimit = |resource, limit: u64| {
+                    let rlimit = libc::rlimit {
+                        rlim_cur: limit as libc::rlim_t,
+                        rlim_max: limit as libc::rlim_t,
+                    };
+                    check(libc::setrlimit(resource, &rlimit) as libc::c_long)
+                };
+                let null = std::ptr::null();
+
+                check(libc::unshare(
+                    libc::CLONE_NEWUSER
+                        | libc::CLONE_NEWNS
+                        | libc::CLONE_NEWPID
+                        | libc::CLONE_NEWNET
+                        | libc::CLONE_NEWIPC
+                        | libc::CLONE_NEWUTS,
+                ) as libc::c_long)?;
+                write_file(&setgroups, b"deny")?;
+                write_file(&uid_map_file, &uid_map)?;
+                write_file(&gid_map_file, &gid_map)?;
+
+                // Only the children enter the new PID namespace.
+                let mut status_pipe = [0; 2];
+                check(libc::pipe2(status_pipe.as_mut_ptr(), libc::O_CLOEXEC) as libc::c_long)?;
+                match libc::fork() {
+                    -1 => return Err(io::Error::last_os_error()),
+                    0 => {}
+                    init => supervise(init, status_pipe[0]),
+                }
+                // The init is killed with the spawned process, and all of the
+                // processes in the namespace are killed with the init.
+                check(libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGKILL) as libc::c_long)?;
+                match libc::fork() {
+                    -1 => return Err(io::Error::last_os_error()),
+                    0 => {}
+                    program => reap(program, status_pipe[1]),
+                }
+
+                // The mounts are not propagated back to the host.
+                mount(null, &root, null, libc::MS_REC | libc::MS_PRIVATE)?;
+                // Only the processes in the namespace are shown.
+                mount(
+                    proc_type.as_ptr(),
+                    &proc,
+                    proc_type.as_ptr(),
+                    libc::MS_NOSUID | libc::MS_NODEV | libc::MS_NOEXEC,
+                )?;
+                for (path, is_dir) in hidden.iter() {
+                    match is_dir {
+                        true => mount(tmpfs_type.as_ptr(), path, tmpfs_type.as_ptr(), 0)?,
+                        false => mount(empty_file.as_ptr(), path, null, libc::MS_BIND)?,
+                    }
+                }
+                // The scratch directory becomes a mount, so that it can be
+                // writable again after all of the mounts are read-only.
+                mount(
+                    scratch_dir.as_ptr(),
+                    &scratch_dir,
+                    null,
+                    libc::MS_BIND | libc::MS_REC,
+                )?;
+                mount_setattr(
+                    &root,
+                    AT_RECURSIVE,
+                    &MountAttr {
+                        attr_set: MOUNT_ATTR_RDONLY,
+                        ..Default::default()
+                    },
+                )?;
+                mount_setattr(
+                    &scratch_dir,
+                    0,
+                    &MountAttr {
+                        attr_clr: MOUNT_ATTR_RDONLY,
+                        ..Default::default()
+                    },
+                )?;
+                // The working directory is entered before, so it is still on
+                // the old mount.
+                if let Some(dir) = &current_dir {
+                    check(libc::chdir(dir.as_ptr()) as libc::c_long)?;
+                }
+
+                set_rlimit(libc::RLIMIT_NOFILE, MAX_OPEN_FILES)?;
+                // Counted in the new user namespace only.
+                set_rlimit(libc::RLIMIT_NPROC, MAX_PROCESSES)?;
+
+                check(libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) as libc::c_long)?;
+                let program = libc::sock_fprog {
+                    len: filter.len() as libc::c_ushort,
+                    filter: filter.as_mut_ptr(),
+                };
+                check(libc::syscall(
+                    libc::SYS_seccomp,
+                    libc::SECCOMP_SET_MODE_FILTER,
+                    0,
+                    &program as *const libc::sock_fprog,
+                ))
+            });
+        }
+        Ok(())
+    }
+
+    /// Run the command in the sandbox.
+    ///
+    /// Error since the sandbox is not supported on this platform.
+    #[cfg(not(all(
+        target_os = "linux",
+        any(target_arch = "x86_64", target_arch = "aarch64")
+    )))]
+    pub fn apply(&self, _command: &mut Command) -> io::Result<()> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "the sandbox is only supported on Linux (x86_64 and aarch64)",
+        ))
+    }
+}
+
+impl Drop for Sandbox {
+    fn drop(&mut self) {
+        fs::remove_dir_all(&self.root).ignore();
+    }
+}
+
+/// Close all file descriptors except `keep`.
+///
+/// # Safety
+///
+/// Called in a forked child only.
+#[cfg(target_os = "linux")]
+unsafe fn close_other_fds(keep: libc::c_int) {
+    let keep = keep as libc::c_uint;
+    if keep > 0 {
+        libc::syscall(libc::SYS_close_range, 0, keep - 1, 0);
+    }
+    libc::syscall(libc::SYS_close_range, keep + 1, libc::c_uint::MAX, 0);
+}
+
+/// Wait for the init of the sandbox in the spawned process, then exit (or
+/// kill itself) with the status of the program, which is read from
+/// `status_fd`. The status of the init is used if the program isn't reaped.
+///
+/// # Safety
+///
+/// Called in the spawned process before `exec` only.
+#[cfg(target_os = "linux")]
+unsafe fn supervise(init: libc::pid_t, status_fd: libc::c_int) -> ! {
+    // Stdio and the pipe reporting the errors of `exec` are left to the
+    // program.
+    close_other_fds(status_fd);
+    let interrupted = || io::Error::last_os_error().kind() == io::ErrorKind::Interrupted;
+    let mut status: libc::c_int = 0;
+    let size = std::mem::size_of::<libc::c_int>();
+    let mut read;
+    loop {
+        read = libc::read(status_fd, (&mut status as *mut libc::c_int).cast(), size);
+        if read != -1 || !interrupted() {
+            break;
+        }
+    }
+    let mut init_status: libc::c_int = 0;
+    while libc::waitpid(init, &mut init_status, 0) == -1 && interrupted() {}
+    if read != size as isize {
+        status = init_status;
+    }
+    if libc::WIFSIGNALED(status) {
+        let signal = libc::WTERMSIG(status);
+        let no_core = libc::rlimit {
+            rlim_cur: 0,
+            rlim_max: 0,
+        };
+        libc::setrlimit(libc::RLIMIT_CORE, &no_core);
+        libc::signal(signal, libc::SIG_DFL);
+        let mut set: libc::sigset_t = std::mem::zeroed();
+        libc::sigemptyset(&mut set);
+        libc::sigaddset(&mut set, signal);
+        libc::sigprocmask(libc::SIG_UNBLOCK, &set, std::ptr::null_mut());
+        libc::kill(libc::getpid(), signal);
+    }
+    libc::_exit(match libc::WIFEXITED(status) {
+        true => libc::WEXITSTATUS(status),
+        false => 128 + libc::WTERMSIG(status),
+    })
+}
+
+/// Reap the processes in the init of the sandbox until the program exits,
+/// then write its status to `status_fd` and exit, which kills the others.
+///
+/// # Safety
+///
+/// Called in the init of the sandbox before `exec` only.
+#[cfg(target_os = "linux")]
+unsafe fn reap(program: libc::pid_t, status_fd: libc::c_int) -> ! {
+    close_other_fds(status_fd);
+    loop {
+        let mut status: libc::c_int = 0;
+        match libc::waitpid(-1, &mut status, 0) {
+            pid if pid == program => {
+                libc::write(
+                    status_fd,
+                    (&status as *const libc::c_int).cast(),
+                    std::mem::size_of::<libc::c_int>(),
+                );
+                libc::_exit(0);
+            }
+            -1 if io::Error::last_os_error().kind() != io::ErrorKind::Interrupted => libc::_exit(1),
+            _ => {}
+        }
+    }
+}
+
+/// Whether the sandboxed program is killed by the seccomp filter.
+pub fn is_violation(failure: &ExitFailure) -> bool {
+    #[cfg(target_os = "linux")]
+    {
+        *failure == ExitFailure::Signal(libc::SIGSYS)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = failure;
+        false
+    }
+}
+
+/// `struct mount_attr` of `mount_setattr(2)`.
+#[cfg(target_os = "linux")]
+#[repr(C)]
+#[derive(Default)]
+struct MountAttr {
+    attr_set: u64,
+    attr_clr: u64,
+    propagation: u64,
+    userns_fd: u64,
+}
+
+#[cfg(target_os = "linux")]
+const MOUNT_ATTR_RDONLY: u64 = 0x1;
+#[cfg(target_os = "linux")]
+const AT_RECURSIVE: libc::c_uint = 0x8000;
+
+/// `AUDIT_ARCH_*` of the architecture, checked by the seccomp filter.
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+const AUDIT_ARCH: u32 = 0xc000_003e;
+#[cfg(all(target_os = "linux", target_arch = "aarch64"))]
+const AUDIT_ARCH: u32 = 0xc000_00b7;
+
+/// Get the seccomp filter, which kills the program on the system calls of
+/// networking, debugging other processes, mounting, namespaces, kernel
+/// modules and keyrings.
+#[cfg(all(
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+))]
+fn seccomp_filter() -> Vec<libc::sock_filter> {
+    // BPF instructions, see `linux/filter.h`.
+    const LD_W_ABS: u16 = 0x20;
+    const JEQ_K: u16 = 0x15;
+    const JGE_K: u16 = 0x35;
+    const RET_K: u16 = 0x06;
+    // Offsets in `struct seccomp_data`.
+    const NR_OFFSET: u32 = 0;
+    const ARCH_OFFSET: u32 = 4;
+    const SECCOMP_RET_ALLOW: u32 = 0x7fff_0000;
+    const DENIED: &[libc::c_long] = &[
+        libc::SYS_socket,
+        libc::SYS_ptrace,
+        libc::SYS_process_vm_readv,
+        libc::SYS_process_vm_writev,
+        libc::SYS_mount,
+        libc::SYS_umount2,
+        libc::SYS_pivot_root,
+        libc::SYS_chroot,
+        libc::SYS_unshare,
+        libc::SYS_setns,
+        libc::SYS_reboot,
+        libc::SYS_kexec_load,
+        libc::SYS_init_module,
+        libc::SYS_finit_module,
+        libc::SYS_delete_module,
+        libc::SYS_bpf,
+        libc::SYS_perf_event_open,
+        libc::SYS_keyctl,
+        libc::SYS_add_key,
+        libc::SYS_request_key,
+        libc::SYS_swapon,
+        libc::SYS_swapoff,
+        libc::SYS_pidfd_open,
+        libc::SYS_pidfd_getfd,
+        libc::SYS_pidfd_send_signal,
+    ];
+    let statement = |code: u16, k: u32| libc::sock_filter {
+        code,
+        jt: 0,
+        jf: 0,
+        k,
+    };
+    let jump = |code: u16, k: u32, jt: u8, jf: u8| libc::sock_filter { code, jt, jf, k };
+    let kill = statement(RET_K, libc::SECCOMP_RET_KILL_PROCESS);
+
+    let mut filter = vec![
+        statement(LD_W_ABS, ARCH_OFFSET),
+        jump(JEQ_K, AUDIT_ARCH, 1, 0),
+        kill,
+        statement(LD_W_ABS, NR_OFFSET),
+    ];
+    if cfg!(target_arch = "x86_64") {
+        // The x32 system calls have the same numbers with this bit set.
+        filter.extend([jump(JGE_K, 0x4000_0000, 0, 1), kill]);
+    }
+    for nr in DENIED {
+        filter.extend([jump(JEQ_K, *nr as u32, 0, 1), kill]);
+    }
+    filter.push(statement(RET_K, SECCOMP_RET_ALLOW));
+    filter
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_violation() {
+        assert!(!is_violation(&ExitFailure::Code(1)));
+        #[cfg(target_os = "linux")]
+        {
+            assert!(is_violation(&ExitFailure::Signal(libc::SIGSYS)));
+            assert!(!is_violation(&ExitFailure::Signal(libc::SIGSEGV)));
+        }
+    }
+
+    #[cfg(all(
+        target_os = "linux",
+        any(target_arch = "x86_64", target_arch = "aarch64")
+    ))]
+    #[test]
+    fn test_seccomp_filter() {
+        let filter = seccomp_filter();
+        // Every jump (class `BPF_JMP`) must land inside the filter.
+        for (i, statement) in filter.iter().enumerate() {
+            if statement.code & 0x07 == 0x05 {
+                let next = i + 1 + statement.jt.max(statement.jf) as usize;
+                assert!(next < filter.len());
+            }
+        }
+        assert_eq!(filter[1].k, AUDIT_ARCH);
+        assert!(filter
+            .iter()
+            .any(|statement| statement.k == libc::SYS_socket as u32));
+        assert_eq!(
+            filter.last().map(|statement| statement.k),
+            Some(0x7fff_0000)
+        );
+    }
+}
@@ -0,0 +1,79 @@
+//! Fixed test data provided by files instead of the data generator.
+
+use crate::prelude::*;
+use serde::Serialize;
+
+/// A test case provided by files.
+#[derive(Debug, Clone, Serialize)]
+pub struct TestData {
+    /// The input file.
+    pub input: PathBuf,
+    /// The answer file with the same name as `input` and extension `ans`.
+    /// `None` if it doesn't exist, and the accepted program is run instead.
+    pub answer: Option<PathBuf>,
+}
+
+impl TestData {
+    /// Name of the test case shown in the report.
+    pub fn name(&self) -> String {
+        self.input
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| self.input.to_string_lossy().to_string())
+    }
+
+    /// Find all input files matched by the glob `pattern`, sorted by path.
+    ///
+    /// Error when the pattern is invalid or matches no file.
+    pub fn find(pattern: &str) -> Result<Vec<Self>, String> {
+        let paths = glob::glob(pattern).map_err(|err| {
+            format!(
+                "Invalid pattern `{}` in field `test_data`: {}",
+                pattern, err
+            )
+        })?;
+        let mut test_data = Vec::new();
+        for path in paths {
+            let input = path.map_err(|err| format!("Failed to read test data: {}", err))?;
+            if !input.is_file() {
+                continue;
+            }
+            let answer = Some(input.with_extension("ans")).filter(|answer| answer.is_file());
+            test_data.push(Self { input, answer });
+        }
+        if test_data.is_empty() {
+            return Err(format!(
+                "No file is matched by `{}` in field `test_data`",
+                pattern
+            ));
+        }
+        test_data.sort_by(|a, b| a.input.cmp(&b.input));
+        Ok(test_data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find() {
+        let dir = std::env::temp_dir().join(format!("oi_checker_test_data_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for file in ["b.in", "b.ans", "a.in", "a.out", "c.txt"] {
+            fs::write(dir.join(file), "").unwrap();
+        }
+        let pattern = dir.join("*.in").to_string_lossy().to_string();
+        let test_data = TestData::find(&pattern);
+        let missing = TestData::find(&dir.join("*.none").to_string_lossy());
+        fs::remove_dir_all(&dir).unwrap();
+
+        let test_data = test_data.unwrap();
+        assert_eq!(test_data.len(), 2);
+        assert_eq!(test_data[0].name(), "a.in");
+        assert_eq!(test_data[0].answer, None);
+        assert_eq!(test_data[1].name(), "b.in");
+        assert_eq!(test_data[1].answer, Some(dir.join("b.ans")));
+        assert!(missing.unwrap_err().contains("No file"));
+    }
+}
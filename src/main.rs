@@ -1,21 +1,47 @@
 //! An OI automatic checker, able to detect AC, WA and TLE
 
+// Keep the naming and signature style used across the crate (`TLE`,
+// `&PathBuf` parameters, `CheckerError::*Error`, ...).
+#![allow(
+    clippy::ptr_arg,
+    clippy::upper_case_acronyms,
+    clippy::enum_variant_names,
+    clippy::redundant_static_lifetimes,
+    clippy::wrong_self_convention,
+    clippy::boxed_local,
+    clippy::doc_overindented_list_items
+)]
+
 mod checker_error;
 mod clean_files;
 mod compilation;
 mod config;
 mod launch;
 mod logging;
+mod manifest;
 mod prelude;
+mod report;
+mod score;
 mod util;
 
 use once_cell::sync::Lazy;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::SystemTime;
 use threadpool::ThreadPool;
 
+use crate::clean_files::AutoRemoveFiles;
+use crate::compilation::diagnostic::{Diagnostic, Severity};
+use crate::compilation::CompilerOutput;
 use crate::config::Config;
-use crate::launch::{LaunchSuiteEnum, LaunchSuiteResult, SuiteLauncher};
+use crate::launch::diff_tool::DiffTool;
+use crate::launch::{LaunchSuiteEnum, LaunchSuiteResult, SuiteLauncher, TimeMeasurement};
 use crate::logging::{Level, Logger};
+use crate::manifest::{Manifest, ProgramHash};
 use crate::prelude::*;
+use crate::report::{TestRecord, VERDICTS};
+use crate::score::Scores;
 
 const LOGGER_DEFAULT_LEVEL: Level = if cfg!(debug_assertions) {
     Level::Trace
@@ -30,13 +56,120 @@ fn main() {
     oi_checker.run().unwrap_or_else(|err| {
         oi_checker.handle_error(err);
     });
+    if launch::is_interrupted() {
+        LOGGER.warning("Program is interrupted.");
+        std::process::exit(130);
+    }
     LOGGER.info("Program exits successfully.");
 }
 
+/// Summary of all launch results, generated in `get_launch_result` step.
+struct LaunchSummary {
+    /// Count of each result of all tested programs:
+    /// `[AC, UK, TLE, WA, MLE, RE, PE, PC, SV]`
+    launch_result_count: [u32; 9],
+    /// Count of each result of every tested program, in the same order.
+    program_result_count: Vec<[u32; 9]>,
+    /// Indexes of the suites accepted for all tested programs.
+    ac_launch_indexes: Vec<u32>,
+    /// The largest peak memory (in KiB) among MLE cases.
+    mle_peak_memory: Option<u64>,
+    /// Index of the first failing (not AC) case.
+    first_failure: Option<u32>,
+    /// Records of all cases, sorted by index.
+    records: Vec<TestRecord>,
+}
+
+impl LaunchSummary {
+    /// Get an empty summary of `programs` tested programs.
+    fn new(programs: usize) -> Self {
+        Self {
+            launch_result_count: [0; 9],
+            program_result_count: vec![[0; 9]; programs],
+            ac_launch_indexes: Vec::new(),
+            mle_peak_memory: None,
+            first_failure: None,
+            records: Vec::new(),
+        }
+    }
+
+    /// Count the record of a finished case.
+    fn add(&mut self, record: TestRecord) {
+        let verdict_idx = VERDICTS
+            .iter()
+            .position(|verdict| *verdict == record.verdict)
+            .expect("Verdict should be one of `VERDICTS`");
+        self.launch_result_count[verdict_idx] += 1;
+        self.program_result_count[record.program][verdict_idx] += 1;
+        match record.verdict {
+            "AC" => {
+                let programs = self.program_result_count.len();
+                let ac_count = self
+                    .records
+                    .iter()
+                    .filter(|r| r.index == record.index && r.verdict == "AC")
+                    .count();
+                if ac_count + 1 == programs {
+                    self.ac_launch_indexes.push(record.index);
+                }
+            }
+            "MLE" => {
+                self.mle_peak_memory = self
+                    .mle_peak_memory
+                    .max(Some(record.peak_memory_kib.unwrap_or(0)));
+            }
+            _ => (),
+        }
+        if record.verdict != "AC" && self.first_failure.is_none() {
+            self.first_failure = Some(record.index);
+        }
+        self.records.push(record);
+    }
+
+    /// Number of finished cases of all tested programs.
+    fn total(&self) -> u32 {
+        self.launch_result_count.iter().sum()
+    }
+
+    /// Number of finished suites. Each suite has a case for every tested
+    /// program.
+    fn suites(&self) -> u32 {
+        self.total() / self.program_result_count.len() as u32
+    }
+
+    /// Score the finished cases of a tested program by the subtasks. In
+    /// stress-test mode, all finished cases are scored equally.
+    fn scores(&self, config: &Config, program: usize) -> Scores {
+        let records: Vec<TestRecord> = self
+            .records
+            .iter()
+            .filter(|record| record.program == program)
+            .cloned()
+            .collect();
+        match config.until_fail {
+            Some(_) => Scores::new(&[], &records, self.suites()),
+            None => Scores::new(&config.subtasks, &records, config.test_cases),
+        }
+    }
+}
+
 struct OIChecker {
     config: Config,
+    /// The tested programs before compilation, used as their names.
+    tested_sources: Vec<PathBuf>,
     created_work_dir: bool,
     launch_started: bool,
+    /// Set to stop launching new suites.
+    stop_launching: Arc<AtomicBool>,
+    /// Reuse the existing inputs instead of running the data generator.
+    reuse_inputs: bool,
+    /// Reuse the existing accepted outputs instead of running the accepted
+    /// program.
+    reuse_answers: bool,
+    /// The manifest of the run. `None` if it isn't written.
+    manifest: Option<Manifest>,
+    /// Records of a previous run, whose suites are not launched again.
+    resumed_records: Vec<TestRecord>,
 }
 
 impl OIChecker {
@@ -45,28 +178,317 @@ impl OIChecker {
         let config = config::get_config()?;
         LOGGER.info("Program begins running.");
         Ok(Self {
+            tested_sources: config.tested_programs.clone(),
             config,
             created_work_dir: false,
             launch_started: false,
+            stop_launching: Arc::new(AtomicBool::new(false)),
+            reuse_inputs: false,
+            reuse_answers: false,
+            manifest: None,
+            resumed_records: Vec::new(),
         })
     }
 
     /// Main function, run the checker
     fn run(&mut self) -> CheckerResult<()> {
         self.created_work_dir = self.init_working_directory()?;
+        self.handle_interrupt();
+        if let Some(index) = self.config.replay {
+            return self.replay(index);
+        }
+        if self.config.watch {
+            return self.watch();
+        }
+        let programs = self.get_programs();
         self.compile_all()?;
         self.launch_started = true;
+        self.start_manifest(&programs);
+        let mut summary = self.launch_all(false)?;
+        if launch::is_interrupted() {
+            LOGGER.warning(&format!(
+                "Interrupted: {} test suites are finished. Only their files are removed.",
+                summary.suites()
+            ));
+        }
+        self.report_total_score(&summary);
+        self.clean_generated_files(&summary);
+        self.write_reports(&mut summary);
+        Ok(())
+    }
+
+    /// Write the header of the manifest with the hashes of `programs` (before
+    /// compilation). With `resume`, the records of the previous run are loaded
+    /// first if the programs are not changed, and kept in the new manifest.
+    ///
+    /// Failures are only warned since the manifest is not necessary.
+    fn start_manifest(&mut self, programs: &[(PathBuf, Stage)]) {
+        let hashes: io::Result<Vec<ProgramHash>> = programs
+            .iter()
+            .map(|(program, _)| ProgramHash::new(program))
+            .collect();
+        let hashes = match hashes {
+            Ok(hashes) => hashes,
+            Err(err) => {
+                LOGGER.warning(&format!(
+                    "Hash programs failed, no manifest is written: {}",
+                    err
+                ));
+                return;
+            }
+        };
+        let work_dir = &self.config.working_directory;
+        if self.config.resume && self.config.until_fail.is_some() {
+            LOGGER.warning("Resuming is not supported in stress-test mode.");
+        } else if self.config.resume {
+            match Manifest::load(work_dir, &hashes) {
+                Ok(previous) => {
+                    // Suites not finished for all tested programs are launched
+                    // again.
+                    let programs = self.config.tested_programs.len();
+                    let records = previous.records;
+                    self.resumed_records = records
+                        .iter()
+                        .filter(|record| {
+                            record.index <= self.config.test_cases
+                                && records.iter().filter(|r| r.index == record.index).count()
+                                    == programs
+                        })
+                        .cloned()
+                        .collect();
+                    self.config.seed = previous.seed;
+                    LOGGER.info(&format!(
+                        "Resume the previous run: {} test suites are finished.",
+                        self.resumed_records.len() / programs
+                    ));
+                }
+                Err(reason) => {
+                    LOGGER.warning(&format!("The previous run can't be resumed: {}.", reason))
+                }
+            }
+        }
+        match Manifest::create(work_dir, &self.config, hashes) {
+            Ok(manifest) => {
+                for record in self.resumed_records.iter() {
+                    manifest.append(record).ignore();
+                }
+                self.manifest = Some(manifest);
+            }
+            Err(err) => LOGGER.warning(&format!("Write manifest failed: {}", err)),
+        }
+    }
+
+    /// Launch all suites and get the summary.
+    ///
+    /// `quiet` --- don't print the result of each test.
+    fn launch_all(&self, quiet: bool) -> CheckerResult<LaunchSummary> {
+        self.stop_launching.store(false, Ordering::SeqCst);
+        if !quiet && self.config.test_data.is_none() {
+            LOGGER.info(&format!("Seed: {}", self.config.seed));
+        }
         let (_pool, rx) = self.launch_suites()?;
-        let (launch_result_count, ac_launch_indexes) = self.get_launch_result(rx);
+        let summary = self.get_launch_result(rx, quiet);
         LOGGER.info("Test finished.");
-        self.report_total_score(launch_result_count);
-        self.clean_generated_files(ac_launch_indexes);
+        Ok(summary)
+    }
+
+    /// Replay the test case `index` of a previous run, reusing its input and
+    /// accepted output in the working directory if they exist, and print the
+    /// verdict with the difference (or checker message). No file is removed.
+    fn replay(&mut self, index: u32) -> CheckerResult<()> {
+        const MAX_SHOWN_LINES: usize = 50;
+        self.compile_all()?;
+        self.launch_started = true;
+        self.reuse_inputs = true;
+        self.reuse_answers = true;
+        let data_file = self.get_data_file(index);
+        let ac_file = self
+            .config
+            .working_directory
+            .join(format!("ac{}.out", index));
+        if self.config.test_data.is_none() && data_file.is_file() {
+            LOGGER.info(&format!("Reuse input file {}.", data_file.display()));
+        }
+        if self.config.interactor.is_none() && ac_file.is_file() {
+            LOGGER.info(&format!(
+                "Reuse accepted output file {}.",
+                ac_file.display()
+            ));
+        }
+        let (tx, rx) = mpsc::channel();
+        SuiteLauncher::from(&*self).run_suite(index, tx);
+        let summary = self.get_launch_result(rx, false);
+        for record in summary.records.iter() {
+            let Some(content) = record
+                .log_file
+                .as_ref()
+                .and_then(|log_file| fs::read_to_string(log_file).ok())
+            else {
+                continue;
+            };
+            let lines: Vec<&str> = content.lines().collect();
+            let mut shown = lines
+                .iter()
+                .take(MAX_SHOWN_LINES)
+                .cloned()
+                .collect::<Vec<_>>()
+                .join("\n");
+            if lines.len() > MAX_SHOWN_LINES {
+                shown.push_str(&format!("\n... ({} lines in total)", lines.len()));
+            }
+            LOGGER.info(&format!(
+                "Test {}: {}\n{}",
+                self.get_case_name(record.index, record.program),
+                match record.verdict {
+                    "WA" => "Difference:",
+                    _ => "Checker message:",
+                },
+                shown
+            ));
+        }
         Ok(())
     }
 
+    /// Watch mode: compile and launch like `self.run`, then poll the
+    /// modification time of the source files. When any of them changes,
+    /// recompile the changed ones and relaunch all suites with a compact
+    /// summary. The generated inputs are reused unless the data generator
+    /// changed, and no file is removed.
+    ///
+    /// Compile errors are reported without exiting. Return only on other
+    /// errors.
+    fn watch(&mut self) -> CheckerResult<()> {
+        const POLL_INTERVAL: Duration = Duration::from_millis(500);
+        let programs = self.get_programs();
+        let get_modified =
+            |program: &PathBuf| fs::metadata(program).and_then(|m| m.modified()).ok();
+        let mut modified: Vec<Option<SystemTime>> = programs
+            .iter()
+            .map(|(program, _)| get_modified(program))
+            .collect();
+        let mut changed: Vec<usize> = (0..programs.len()).collect();
+        let mut compiled = false;
+        let mut inputs_outdated = true;
+        let mut run = 0;
+        loop {
+            if !changed.is_empty() {
+                inputs_outdated |= changed
+                    .iter()
+                    .any(|&i| matches!(programs[i].1, Stage::CompileDG));
+                let result = if compiled {
+                    // The targets are the same as before, so the config is
+                    // not updated.
+                    let changed_programs: Vec<(PathBuf, Stage)> =
+                        changed.iter().map(|&i| programs[i].clone()).collect();
+                    self.compile_programs(&changed_programs).map(|_| ())
+                } else {
+                    self.compile_all()
+                };
+                match result {
+                    Ok(()) => {
+                        compiled = true;
+                        self.launch_started = true;
+                        self.reuse_inputs = !inputs_outdated;
+                        inputs_outdated = false;
+                        run += 1;
+                        let mut summary = self.launch_all(run > 1)?;
+                        if launch::is_interrupted() {
+                            return Ok(());
+                        }
+                        if run == 1 {
+                            self.report_total_score(&summary);
+                        } else {
+                            self.report_compact(&summary, run);
+                        }
+                        self.write_reports(&mut summary);
+                    }
+                    Err(err) => err.report(),
+                }
+                LOGGER.info("Watching the source files for changes. Press Ctrl+C to exit.");
+            }
+            thread::sleep(POLL_INTERVAL);
+            if launch::is_interrupted() {
+                return Ok(());
+            }
+            changed = (0..programs.len())
+                .filter(|&i| {
+                    let new_modified = get_modified(&programs[i].0);
+                    let is_changed = new_modified != modified[i];
+                    modified[i] = new_modified;
+                    is_changed
+                })
+                .collect();
+            if !changed.is_empty() {
+                LOGGER.info(&format!(
+                    "Changed: {}",
+                    changed
+                        .iter()
+                        .map(|&i| programs[i].0.display().to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ));
+            }
+        }
+    }
+
+    /// Print a compact summary of a rerun in watch mode: the counts of the
+    /// results, the score and the failed tests of each tested program.
+    fn report_compact(&self, summary: &LaunchSummary, run: u32) {
+        const MAX_SHOWN_FAILURES: usize = 10;
+        for (program, launch_result_count) in summary.program_result_count.iter().enumerate() {
+            let counts: Vec<String> = launch_result_count
+                .iter()
+                .zip(VERDICTS)
+                .filter(|(count, _)| **count != 0)
+                .map(|(count, name)| format!("{} {}", name, count))
+                .collect();
+            let mut failures: Vec<&TestRecord> = summary
+                .records
+                .iter()
+                .filter(|record| record.program == program && record.verdict != "AC")
+                .collect();
+            failures.sort_by_key(|record| record.index);
+            let mut line = format!(
+                "Run #{}{}: {} / Total {}, Score {:.1} / 100",
+                run,
+                match self.tested_sources.len() {
+                    1 => String::new(),
+                    _ => format!(" [{}]", self.tested_sources[program].display()),
+                },
+                counts.join(" "),
+                launch_result_count.iter().sum::<u32>(),
+                summary.scores(&self.config, program).total,
+            );
+            if !failures.is_empty() {
+                line.push_str(". Failed: ");
+                line.push_str(
+                    &failures
+                        .iter()
+                        .take(MAX_SHOWN_FAILURES)
+                        .map(|record| format!("{} {}", record.name, record.verdict))
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                );
+                if failures.len() > MAX_SHOWN_FAILURES {
+                    line.push_str(", ...");
+                }
+            }
+            if failures.is_empty() {
+                LOGGER.info(&console::style(line).green().to_string());
+            } else {
+                LOGGER.warning(&line);
+            }
+        }
+    }
+
     fn handle_error(&self, err: Box<CheckerError>) -> ! {
         if self.created_work_dir && !self.launch_started {
-            if let Err(err) = fs::remove_dir_all(self.config.working_directory.as_path()) {
+            if let Some(log_file) = err.get_log_file() {
+                LOGGER.info(&format!(
+                    "The temporary working directory is kept for the compiler output {}.",
+                    log_file.display()
+                ));
+            } else if let Err(err) = fs::remove_dir_all(self.config.working_directory.as_path()) {
                 LOGGER.warning(&format!("Failed to remove working directory: {}", err));
             } else {
                 LOGGER.info("An error occurs, so the temporary working directory removed.");
@@ -75,6 +497,28 @@ impl OIChecker {
         err.destruct();
     }
 
+    /// Handle Ctrl+C: stop launching new suites and kill the running programs,
+    /// so that the finished suites are still reported. Exit at once on the
+    /// second Ctrl+C.
+    fn handle_interrupt(&self) {
+        let stop_launching = self.stop_launching.clone();
+        let result = ctrlc::set_handler(move || {
+            if launch::is_interrupted() {
+                LOGGER.fatal("Interrupted again, exit immediately.");
+                std::process::exit(130);
+            }
+            LOGGER.warning(
+                "Interrupted, stopping the running test suites. \
+                Press Ctrl+C again to exit immediately.",
+            );
+            launch::interrupt();
+            stop_launching.store(true, Ordering::SeqCst);
+        });
+        if let Err(err) = result {
+            LOGGER.warning(&format!("Failed to handle Ctrl+C: {}", err));
+        }
+    }
+
     /// Create the working directory if it doesn't exist.
     ///
     /// Return if the working directory is created.
@@ -94,32 +538,122 @@ impl OIChecker {
         }
     }
 
-    /// Compile all related files and replace mapped files.
+    /// Get the programs to compile with their stages. A program used more
+    /// than once is listed only once.
+    fn get_programs(&self) -> Vec<(PathBuf, Stage)> {
+        let mut programs: Vec<(PathBuf, Stage)> = Vec::new();
+        match &self.config.test_data {
+            None => {
+                programs.push((self.config.data_generator.clone(), Stage::CompileDG));
+                programs.push((self.config.accepted_program.clone(), Stage::CompileAC));
+            }
+            // The accepted program is needed only when some answer file is missing.
+            Some(test_data) if test_data.iter().any(|t| t.answer.is_none()) => {
+                programs.push((self.config.accepted_program.clone(), Stage::CompileAC));
+            }
+            Some(_) => (),
+        }
+        for tested_program in self.config.tested_programs.iter() {
+            programs.push((tested_program.clone(), Stage::CompileTP));
+        }
+        if let DiffTool::Checker(command) = &self.config.diff_tool {
+            programs.push((PathBuf::from(&command[0]), Stage::CompileCK));
+        }
+        if let Some(interactor) = &self.config.interactor {
+            programs.push((interactor.clone(), Stage::CompileIT));
+        }
+        let mut unique_programs: Vec<(PathBuf, Stage)> = Vec::with_capacity(programs.len());
+        for (program, stage) in programs {
+            if unique_programs.iter().all(|(p, _)| *p != program) {
+                unique_programs.push((program, stage));
+            }
+        }
+        unique_programs
+    }
+
+    /// Compile all related files concurrently (at most `test_threads` at the
+    /// same time) and replace mapped files. A program used more than once is
+    /// compiled only once.
     ///
-    /// Return `Err(_)` when `self.compile_one` failed.
+    /// Return `Err(_)` with all of the errors when `self.compile_one` failed.
     fn compile_all(&mut self) -> CheckerResult<()> {
-        macro_rules! compile_one {
-            ($program: ident, $stage: expr) => {
-                if let Some(target) = self.compile_one(&self.config.$program, $stage)? {
-                    self.config.$program = target;
-                }
-            };
+        let targets = self.compile_programs(&self.get_programs())?;
+        let get_target = |program: &PathBuf| targets.get(program).unwrap_or(program).to_owned();
+        let config = &mut self.config;
+        config.data_generator = get_target(&config.data_generator);
+        config.accepted_program = get_target(&config.accepted_program);
+        for tested_program in config.tested_programs.iter_mut() {
+            *tested_program = get_target(tested_program);
+        }
+        if let DiffTool::Checker(command) = &mut config.diff_tool {
+            command[0] = get_target(&PathBuf::from(&command[0]))
+                .to_string_lossy()
+                .to_string();
+        }
+        if let Some(interactor) = &mut config.interactor {
+            *interactor = get_target(interactor);
         }
-        compile_one!(data_generator, Stage::CompileDG);
-        compile_one!(accepted_program, Stage::CompileAC);
-        compile_one!(tested_program, Stage::CompileTP);
         Ok(())
     }
 
+    /// Compile the programs with at most `test_threads` threads.
+    ///
+    /// Return the targets of the compiled programs, or all of the errors.
+    fn compile_programs(
+        &self,
+        programs: &[(PathBuf, Stage)],
+    ) -> CheckerResult<HashMap<PathBuf, PathBuf>> {
+        let next = AtomicUsize::new(0);
+        let (tx, rx) = mpsc::channel();
+        thread::scope(|scope| {
+            for _ in 0..(self.config.test_threads as usize).min(programs.len()) {
+                let tx = tx.clone();
+                let next = &next;
+                scope.spawn(move || loop {
+                    let i = next.fetch_add(1, Ordering::SeqCst);
+                    let Some((program, stage)) = programs.get(i) else {
+                        break;
+                    };
+                    tx.send((i, self.compile_one(program, *stage)))
+                        .expect("Sender should send successfully");
+                });
+            }
+        });
+        drop(tx);
+        let mut results: Vec<(usize, CheckerResult<Option<PathBuf>>)> = rx.into_iter().collect();
+        results.sort_by_key(|(i, _)| *i);
+
+        let mut targets: HashMap<PathBuf, PathBuf> = HashMap::new();
+        let mut errors = Vec::new();
+        for (i, result) in results {
+            match result {
+                Ok(Some(target)) => {
+                    targets.insert(programs[i].0.clone(), target);
+                }
+                Ok(None) => (),
+                Err(err) => errors.push(*err),
+            }
+        }
+        match errors.len() {
+            0 => Ok(targets),
+            1 => Err(Box::new(errors.remove(0))),
+            _ => Err(Box::new(CheckerError::CompileErrors { errors })),
+        }
+    }
+
     /// Launch all suites.
     ///
+    /// In stress-test mode (`until_fail`), each thread keeps launching suites
+    /// with new indexes until `self.stop_launching` is set or the limits are
+    /// reached.
+    ///
     /// Returned value explanation:
     /// - `Err(_)` => Failed to launch programs.
     /// - `Ok((pool, rx))` => Succeed in launching programs. Return the threadpool
     ///    to extend its lifetime, and the receiver for the next step.
     fn launch_suites(&self) -> CheckerResult<(ThreadPool, mpsc::Receiver<LaunchSuiteResult>)> {
         let pool = ThreadPool::new(self.config.test_threads as usize);
-        let suite_launcher: SuiteLauncher = (&*self).into();
+        let suite_launcher: SuiteLauncher = self.into();
         let (tx, rx) = mpsc::channel();
         for i in 0..self.config.test_threads {
             // Warmup
@@ -129,12 +663,46 @@ impl OIChecker {
         }
         LOGGER.info("Warming up...");
 
-        for index in 1..=self.config.test_cases {
-            let suite_launcher = suite_launcher.clone();
-            let tx = tx.clone();
-            pool.execute(move || {
-                suite_launcher.run_suite(index, tx);
-            });
+        match self.config.until_fail {
+            None => {
+                let resumed: HashSet<u32> = self
+                    .resumed_records
+                    .iter()
+                    .map(|record| record.index)
+                    .collect();
+                for index in (1..=self.config.test_cases).filter(|i| !resumed.contains(i)) {
+                    let suite_launcher = suite_launcher.clone();
+                    let tx = tx.clone();
+                    pool.execute(move || {
+                        suite_launcher.run_suite(index, tx);
+                    });
+                }
+            }
+            Some(until_fail) => {
+                let next_index = Arc::new(AtomicU32::new(1));
+                let deadline = until_fail.time_budget.map(|budget| Instant::now() + budget);
+                for _ in 0..self.config.test_threads {
+                    let suite_launcher = suite_launcher.clone();
+                    let tx = tx.clone();
+                    let next_index = next_index.clone();
+                    let stop_launching = self.stop_launching.clone();
+                    pool.execute(move || loop {
+                        if stop_launching.load(Ordering::SeqCst)
+                            || deadline.is_some_and(|deadline| Instant::now() >= deadline)
+                        {
+                            break;
+                        }
+                        let index = next_index.fetch_add(1, Ordering::SeqCst);
+                        if until_fail
+                            .max_cases
+                            .is_some_and(|max_cases| index > max_cases)
+                        {
+                            break;
+                        }
+                        suite_launcher.run_suite(index, tx.clone());
+                    });
+                }
+            }
         }
         Ok((pool, rx))
     }
@@ -143,69 +711,303 @@ impl OIChecker {
     ///
     /// `rx` --- The receiver generated in `launch_suites` step.
     ///
-    /// Return: `((AC count, UK count, TLE count, WA count), Accepted launch indexes)`
-    fn get_launch_result(&self, rx: mpsc::Receiver<LaunchSuiteResult>) -> ([u32; 4], Vec<u32>) {
-        let mut launch_result_count = [0u32, 0u32, 0u32, 0u32];
-        let mut ac_launch_indexes = Vec::new();
-        for _ in 1..=self.config.test_cases {
-            let launch_result = rx.recv().expect("Receiver should receive");
-            let (result_record_idx, log_content) = match launch_result.inner {
-                LaunchSuiteEnum::AC(duration) => {
-                    ac_launch_indexes.push(launch_result.index);
-                    (
-                        0,
-                        format!("AC ({0:.3} ms)", duration.as_secs_f64() * 1000.0),
-                    )
+    /// In stress-test mode, no new suite is launched after the first failing
+    /// one, but the running suites are still received.
+    ///
+    /// Return the summary of all launch results.
+    /// `quiet` --- don't print the result of each test.
+    fn get_launch_result(
+        &self,
+        rx: mpsc::Receiver<LaunchSuiteResult>,
+        quiet: bool,
+    ) -> LaunchSummary {
+        let mut summary = LaunchSummary::new(self.config.tested_programs.len());
+        for record in self.resumed_records.iter() {
+            summary.add(record.to_owned());
+        }
+        let write_hint = |log_result: &io::Result<()>| {
+            if log_result.is_ok() {
+                ""
+            } else {
+                "[write failed]"
+            }
+        };
+        for launch_result in rx {
+            let record = TestRecord::new(
+                &launch_result,
+                self.get_suite_name(launch_result.index),
+                self.tested_sources[launch_result.program].to_owned(),
+                self.get_data_file(launch_result.index),
+                &self.config.working_directory,
+            );
+            if let Some(manifest) = &self.manifest {
+                if let Err(err) = manifest.append(&record) {
+                    LOGGER.warning(&format!("Write manifest failed: {}", err));
                 }
-                LaunchSuiteEnum::TLE(duration) => (
-                    2,
-                    format!("TLE ({0:.3} ms)", duration.as_secs_f64() * 1000.0),
+            }
+            let failed = record.verdict != "AC";
+            summary.add(record);
+            if failed && self.config.until_fail.is_some() {
+                self.stop_launching.store(true, Ordering::SeqCst);
+            }
+            if quiet {
+                continue;
+            }
+            let name = self.get_case_name(launch_result.index, launch_result.program);
+            let log_content = match launch_result.inner {
+                LaunchSuiteEnum::AC(usage) => format!("AC ({})", usage),
+                LaunchSuiteEnum::TLE(usage) => format!("TLE ({})", usage),
+                LaunchSuiteEnum::MLE(usage) => format!(
+                    "MLE ({0}, {1:.3} MiB)",
+                    usage,
+                    usage.peak_memory.unwrap_or(0) as f64 / 1024.0
                 ),
-                LaunchSuiteEnum::WA(duration, file, log_result) => (
-                    3,
-                    format!(
-                        "WA ({0:.3} ms) : See difference in file {1}{2}",
-                        duration.as_secs_f64() * 1000.0,
-                        file.display(),
-                        if log_result.is_ok() {
-                            ""
-                        } else {
-                            "[write failed]"
-                        }
-                    ),
+                LaunchSuiteEnum::RE(usage, failure) => format!("RE ({}) : {}", usage, failure),
+                LaunchSuiteEnum::SV(usage) => format!(
+                    "SV ({}) : Security violation (forbidden system call)",
+                    usage
                 ),
-                LaunchSuiteEnum::UK(hint) => (1, format!("UK: {}", hint)),
+                LaunchSuiteEnum::OutputMissing(usage, name) => {
+                    format!("WA ({}) : Output file {} is missing", usage, name)
+                }
+                LaunchSuiteEnum::WA(usage, file, log_result) => format!(
+                    "WA ({0}) : See difference in file {1}{2}",
+                    usage,
+                    file.display(),
+                    write_hint(&log_result)
+                ),
+                LaunchSuiteEnum::PE(usage, file, log_result) => format!(
+                    "PE ({0}) : See checker message in file {1}{2}",
+                    usage,
+                    file.display(),
+                    write_hint(&log_result)
+                ),
+                LaunchSuiteEnum::PC(usage, points, file, log_result) => format!(
+                    "PC {0:.1}% ({1}) : See checker message in file {2}{3}",
+                    points * 100.0,
+                    usage,
+                    file.display(),
+                    write_hint(&log_result)
+                ),
+                LaunchSuiteEnum::UK(hint) => format!("UK: {}", hint),
             };
-            launch_result_count[result_record_idx] += 1;
-            LOGGER.info(&format!(
-                "Test #{0:02}: {1}",
-                launch_result.index, log_content
-            ));
+            // The seed is shown for failing cases so that they can be replayed.
+            let seed_hint = if failed && self.config.test_data.is_none() {
+                format!(
+                    " [seed {}]",
+                    launch::get_case_seed(self.config.seed, launch_result.index)
+                )
+            } else {
+                String::new()
+            };
+            LOGGER.info(&format!("Test {0}: {1}{2}", name, log_content, seed_hint));
+            if let Some(minimized_input) = launch_result.minimized_input {
+                LOGGER.info(&format!(
+                    "Test {0}: Minimized input saved to file {1}",
+                    name,
+                    minimized_input.display()
+                ));
+            }
+        }
+        summary
+    }
+
+    /// Get the name of a test case shown in the console: the name of the
+    /// suite, followed by the tested program if there are more than one.
+    fn get_case_name(&self, index: u32, program: usize) -> String {
+        match self.tested_sources.len() {
+            1 => self.get_suite_name(index),
+            _ => format!(
+                "{} [{}]",
+                self.get_suite_name(index),
+                self.tested_sources[program].display()
+            ),
+        }
+    }
+
+    /// Get the name of a suite shown in the report: the input file name with
+    /// fixed test data, or the index otherwise.
+    fn get_suite_name(&self, index: u32) -> String {
+        match &self.config.test_data {
+            Some(test_data) => test_data[index as usize - 1].name(),
+            None => format!("#{:02}", index),
         }
-        (launch_result_count, ac_launch_indexes)
     }
 
     /// Print total score onto the screen with color.
     ///
-    /// `launch_result_count` --- the array generated in `get_launch_result` step
-    fn report_total_score(&self, launch_result_count: [u32; 4]) {
+    /// `summary` --- the summary generated in `get_launch_result` step
+    ///
+    /// With more than one tested program, a matrix of the results is printed
+    /// first, followed by the report of each program.
+    fn report_total_score(&self, summary: &LaunchSummary) {
+        let multiple = self.tested_sources.len() > 1;
+        if multiple {
+            self.report_matrix(summary);
+        }
+        for (program, launch_result_count) in summary.program_result_count.iter().enumerate() {
+            if multiple {
+                LOGGER.info(&format!(
+                    "Program {}:",
+                    console::style(self.tested_sources[program].display()).bold()
+                ));
+            }
+            self.report_program_score(summary, program, launch_result_count);
+        }
+        if let Some(peak_memory) = summary.mle_peak_memory {
+            LOGGER.info(&format!(
+                "Peak memory of MLE cases: {:.3} MiB",
+                peak_memory as f64 / 1024.0
+            ));
+        }
+        if self.config.until_fail.is_some() {
+            match summary.first_failure {
+                Some(index) => LOGGER.warning(&format!(
+                    "Test {} failed. Input file: {}",
+                    self.get_suite_name(index),
+                    console::style(self.get_data_file(index).display())
+                        .bold()
+                        .underlined(),
+                )),
+                None => LOGGER.info("No failing test is found."),
+            }
+        }
+    }
+
+    /// Print the verdict and the time of each test case in a matrix of test
+    /// cases × tested programs.
+    fn report_matrix(&self, summary: &LaunchSummary) {
+        const CELL_WIDTH: usize = 16;
+        let mut records: Vec<&TestRecord> = summary.records.iter().collect();
+        records.sort_by_key(|record| (record.index, record.program));
+        let name_width = records
+            .iter()
+            .map(|record| record.name.len())
+            .max()
+            .unwrap_or(0)
+            .max(4);
+        let mut header = format!("{:<1$}", "Test", name_width);
+        for (i, _) in self.tested_sources.iter().enumerate() {
+            header.push_str(&format!(
+                " | {:<1$}",
+                format!("Program {}", i + 1),
+                CELL_WIDTH
+            ));
+        }
+        for (i, source) in self.tested_sources.iter().enumerate() {
+            LOGGER.info(&format!("Program {}: {}", i + 1, source.display()));
+        }
+        LOGGER.info(&header);
+        for row in records.chunk_by(|a, b| a.index == b.index) {
+            let mut line = format!("{:<1$}", row[0].name, name_width);
+            for program in 0..self.tested_sources.len() {
+                let cell = match row.iter().find(|record| record.program == program) {
+                    Some(record) => {
+                        let time_ms = match self.config.time_measurement {
+                            TimeMeasurement::Cpu => record.cpu_time_ms.or(record.wall_time_ms),
+                            TimeMeasurement::Wall => record.wall_time_ms,
+                        };
+                        let text = match time_ms {
+                            Some(time_ms) => format!("{} {:.1} ms", record.verdict, time_ms),
+                            None => record.verdict.to_owned(),
+                        };
+                        let text = format!("{:<1$}", text, CELL_WIDTH);
+                        match record.verdict {
+                            "AC" => console::style(text).green(),
+                            "PC" => console::style(text).cyan(),
+                            "UK" => console::style(text).yellow(),
+                            _ => console::style(text).red(),
+                        }
+                        .to_string()
+                    }
+                    None => format!("{:<1$}", "-", CELL_WIDTH),
+                };
+                line.push_str(" | ");
+                line.push_str(&cell);
+            }
+            LOGGER.info(&line);
+        }
+    }
+
+    /// Print the total and the score of a tested program.
+    fn report_program_score(
+        &self,
+        summary: &LaunchSummary,
+        program: usize,
+        launch_result_count: &[u32; 9],
+    ) {
         LOGGER.info(&format!(
-            "Report: AC {} UK {} TLE {} WA {} / Total {}",
+            "Report: AC {} PC {} UK {} TLE {} MLE {} RE {} SV {} PE {} WA {} / Total {}",
             console::style(launch_result_count[0]).green().bold(),
+            console::style(launch_result_count[7]).cyan().bold(),
             console::style(launch_result_count[1]).yellow().bold(),
             console::style(launch_result_count[2]).red().bold(),
+            console::style(launch_result_count[4]).red().bold(),
+            console::style(launch_result_count[5]).red().bold(),
+            console::style(launch_result_count[8]).red().bold(),
+            console::style(launch_result_count[6]).red().bold(),
             console::style(launch_result_count[3]).red().bold(),
-            console::style(self.config.test_cases).bold(),
+            console::style(launch_result_count.iter().sum::<u32>()).bold(),
+        ));
+        let scores = summary.scores(&self.config, program);
+        for (i, (subtask, score)) in self
+            .config
+            .subtasks
+            .iter()
+            .zip(scores.subtasks.iter())
+            .enumerate()
+        {
+            LOGGER.info(&format!(
+                "Subtask #{} (tests {}-{}, {}): {} / {}",
+                i + 1,
+                subtask.first,
+                subtask.last,
+                subtask.policy,
+                console::style(format!("{:.1}", score)).bold(),
+                subtask.points,
+            ));
+        }
+        LOGGER.info(&format!(
+            "Score: {} / 100",
+            console::style(format!("{:.1}", scores.total)).cyan().bold()
         ));
     }
 
+    /// Get the input file of a suite.
+    fn get_data_file(&self, index: u32) -> PathBuf {
+        match &self.config.test_data {
+            Some(test_data) => test_data[index as usize - 1].input.to_owned(),
+            None => self
+                .config
+                .working_directory
+                .join(format!("data{}.in", index)),
+        }
+    }
+
     /// Clean generated files with `self.config.auto_remove_files` setting.
     ///
-    /// `ac_launch_indexes` --- the vec generated in `get_launch_result` step
-    fn clean_generated_files(&self, ac_launch_indexes: Vec<u32>) {
-        match self.config.auto_remove_files.run(
-            ac_launch_indexes,
-            self.config.test_cases,
+    /// In stress-test mode, the files of failing cases are always kept. Only
+    /// the files of finished cases are removed if the checker is interrupted.
+    ///
+    /// `summary` --- the summary generated in `get_launch_result` step
+    fn clean_generated_files(&self, summary: &LaunchSummary) {
+        let auto_remove_files = match (
+            &self.config.auto_remove_files,
+            &self.config.until_fail,
+            summary.first_failure,
+        ) {
+            (AutoRemoveFiles::Always, Some(_), Some(_)) => &AutoRemoveFiles::AC,
+            (auto_remove_files, _, _) => auto_remove_files,
+        };
+        let mut finished_indexes: Vec<u32> =
+            summary.records.iter().map(|record| record.index).collect();
+        finished_indexes.sort();
+        finished_indexes.dedup();
+        match auto_remove_files.run(
+            summary.ac_launch_indexes.to_owned(),
+            finished_indexes,
+            !launch::is_interrupted(),
+            self.config.tested_programs.len(),
             &self.config.working_directory,
             self.created_work_dir,
         ) {
@@ -214,6 +1016,50 @@ impl OIChecker {
         };
     }
 
+    /// Write the reports in `self.config.report`. Called after the files are
+    /// cleaned, so that only the remaining files are recorded.
+    fn write_reports(&self, summary: &mut LaunchSummary) {
+        if self.config.report.is_empty() {
+            return;
+        }
+        summary
+            .records
+            .sort_by_key(|record| (record.index, record.program));
+        for record in summary.records.iter_mut() {
+            record.forget_missing_files();
+        }
+        for report in self.config.report.iter() {
+            let path = report.path(&self.config);
+            match report.write(&self.config, summary) {
+                Ok(_) => LOGGER.info(&format!("Write report to file {}.", path.display())),
+                Err(err) => LOGGER.error(&format!(
+                    "Failed to write report to file {}: {}",
+                    path.display(),
+                    err
+                )),
+            }
+        }
+    }
+
+    /// Print the number of warnings in the output of a successful
+    /// compilation and the first of them.
+    fn report_warnings(&self, program: &PathBuf, output: &CompilerOutput) {
+        const MAX_SHOWN_WARNINGS: usize = 3;
+        let warnings: Vec<&Diagnostic> = output.get(Severity::Warning).collect();
+        if warnings.is_empty() {
+            return;
+        }
+        LOGGER.warning(&format!(
+            "{} warning(s) when compiling {} (see file {}).",
+            warnings.len(),
+            program.display(),
+            output.log_file.display()
+        ));
+        for warning in warnings.iter().take(MAX_SHOWN_WARNINGS) {
+            LOGGER.info(warning);
+        }
+    }
+
     /// Try to compile a program.
     ///
     /// Returned value:
@@ -235,13 +1081,28 @@ impl OIChecker {
             .compilation_rules
             .get_rule(&ext.to_string_lossy().to_string());
         if let Some(rule) = rule {
-            let target = rule.run(&self.config.working_directory, &program, stage)?;
-            LOGGER.info(&format!(
-                "Compile {} successfully to target {}.",
-                program.to_string_lossy(),
-                target
-            ));
-            Ok(Some(target.into()))
+            let compiled = rule.run(&self.config.working_directory, program, stage)?;
+            if let Some(output) = compiled
+                .output
+                .as_ref()
+                .filter(|_| self.config.compile_warnings)
+            {
+                self.report_warnings(program, output);
+            }
+            if compiled.cached {
+                LOGGER.info(&format!(
+                    "Compile {} cached: target {} is up to date.",
+                    program.to_string_lossy(),
+                    compiled.target
+                ));
+            } else {
+                LOGGER.info(&format!(
+                    "Compile {} successfully to target {}.",
+                    program.to_string_lossy(),
+                    compiled.target
+                ));
+            }
+            Ok(Some(compiled.target.into()))
         } else {
             LOGGER.info(&format!(
                 "No matched compilation config for \"{}\" (extension: {}), skip it",
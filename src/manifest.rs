@@ -0,0 +1,109 @@
+//! The manifest of a run, saved in the working directory so that an
+//! interrupted run can be resumed.
+//!
+//! The manifest is a JSON Lines file. The first line is the header with the
+//! config and the hashes of the programs, and each following line is the
+//! record of a finished test case, appended as soon as it arrives.
+
+use crate::config::Config;
+use crate::prelude::*;
+use crate::report::TestRecord;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::Write;
+
+/// File name of the manifest in the working directory.
+pub const MANIFEST_FILE: &str = "manifest.jsonl";
+
+/// The hash of a program source.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProgramHash {
+    pub file: PathBuf,
+    /// SHA-256 of the content, in hex.
+    pub hash: String,
+}
+
+impl ProgramHash {
+    /// Hash the content of the file.
+    pub fn new(file: &PathBuf) -> io::Result<Self> {
+        let content = fs::read(file)?;
+        Ok(Self {
+            file: file.to_owned(),
+            hash: Sha256::digest(content)
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect(),
+        })
+    }
+}
+
+/// The first line of the manifest.
+#[derive(Serialize, Deserialize)]
+struct Header {
+    /// The base seed, reused when the run is resumed.
+    seed: u64,
+    programs: Vec<ProgramHash>,
+    /// Only for reference. It isn't read when the run is resumed.
+    config: serde_json::Value,
+}
+
+/// A previous run loaded from the manifest.
+pub struct PreviousRun {
+    pub seed: u64,
+    pub records: Vec<TestRecord>,
+}
+
+/// The manifest being written.
+pub struct Manifest {
+    file: fs::File,
+}
+
+impl Manifest {
+    /// Create (or overwrite) the manifest and write the header.
+    pub fn create(
+        work_dir: &PathBuf,
+        config: &Config,
+        programs: Vec<ProgramHash>,
+    ) -> io::Result<Self> {
+        let header = Header {
+            seed: config.seed,
+            programs,
+            config: serde_json::to_value(config)?,
+        };
+        let mut file = fs::File::create(work_dir.join(MANIFEST_FILE))?;
+        writeln!(file, "{}", serde_json::to_string(&header)?)?;
+        Ok(Self { file })
+    }
+
+    /// Append the record of a finished test case.
+    pub fn append(&self, record: &TestRecord) -> io::Result<()> {
+        let mut file = &self.file;
+        writeln!(file, "{}", serde_json::to_string(record)?)?;
+        file.flush()
+    }
+
+    /// Load the previous run from the manifest. Records after a broken line
+    /// (e.g. written when the checker crashed) are dropped.
+    ///
+    /// Return `Err(_)` with the reason if the manifest can't be read, or any
+    /// program is different from `programs`.
+    pub fn load(work_dir: &PathBuf, programs: &[ProgramHash]) -> Result<PreviousRun, String> {
+        let content = fs::read_to_string(work_dir.join(MANIFEST_FILE))
+            .map_err(|err| format!("failed to read the manifest ({})", err))?;
+        let mut lines = content.lines();
+        let header: Header = lines
+            .next()
+            .and_then(|line| serde_json::from_str(line).ok())
+            .ok_or_else(|| "the header of the manifest is broken".to_string())?;
+        if header.programs != programs {
+            return Err("the programs are changed since the previous run".into());
+        }
+        let records = lines
+            .map_while(|line| serde_json::from_str(line).ok())
+            .collect();
+        Ok(PreviousRun {
+            seed: header.seed,
+            records,
+        })
+    }
+}
@@ -0,0 +1,227 @@
+//! Reports of a run written to files, for scripts and other tools.
+
+mod html;
+mod json;
+mod junit;
+
+use crate::config::Config;
+use crate::launch::{ExitFailure, LaunchSuiteEnum, LaunchSuiteResult};
+use crate::prelude::*;
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// Short names of the verdicts, in the order of the counts in the summary.
+pub const VERDICTS: [&str; 9] = ["AC", "UK", "TLE", "WA", "MLE", "RE", "PE", "PC", "SV"];
+
+/// Short name of a verdict, one of `VERDICTS`. It isn't borrowed from the
+/// input when deserialized (see `deserialize_verdict`).
+pub type Verdict = &'static str;
+
+/// A report written after all suites finish.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Report {
+    /// Write every test record, the config and the totals as JSON.
+    Json(PathBuf),
+    /// Write a JUnit XML report for CI. `None` means `junit.xml` in the
+    /// working directory.
+    Junit(Option<PathBuf>),
+    /// Write a self-contained HTML report with side-by-side differences.
+    /// `None` means `report.html` in the working directory.
+    Html(Option<PathBuf>),
+}
+
+impl Report {
+    /// Write the report.
+    pub fn write(&self, config: &Config, summary: &crate::LaunchSummary) -> io::Result<()> {
+        let path = self.path(config);
+        match self {
+            Self::Json(_) => json::write(&path, config, summary),
+            Self::Junit(_) => junit::write(&path, config, summary),
+            Self::Html(_) => html::write(&path, config, summary),
+        }
+    }
+
+    /// The file which the report is written to.
+    pub fn path(&self, config: &Config) -> PathBuf {
+        match self {
+            Self::Json(path) | Self::Junit(Some(path)) | Self::Html(Some(path)) => path.to_owned(),
+            Self::Junit(None) => config.working_directory.join("junit.xml"),
+            Self::Html(None) => config.working_directory.join("report.html"),
+        }
+    }
+}
+
+impl TryFrom<&str> for Report {
+    type Error = String;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value.split_once('=') {
+            Some(("json", path)) if !path.is_empty() => Ok(Self::Json(path.into())),
+            Some(("junit", path)) if !path.is_empty() => Ok(Self::Junit(Some(path.into()))),
+            Some(("html", path)) if !path.is_empty() => Ok(Self::Html(Some(path.into()))),
+            None if value == "junit" => Ok(Self::Junit(None)),
+            None if value == "html" => Ok(Self::Html(None)),
+            _ => Err(format!(
+                "`{}` is not allowed in field `report`. Expected `FORMAT=PATH`, \
+                where FORMAT is `json`, `junit` or `html`",
+                value
+            )),
+        }
+    }
+}
+
+/// The record of a finished test suite.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestRecord {
+    pub index: u32,
+    /// The name shown in the console, e.g. `#01`.
+    pub name: String,
+    /// The position of the tested program in `tested_programs`.
+    pub program: usize,
+    /// The tested program (before compilation).
+    pub tested_program: PathBuf,
+    /// Short name of the verdict, e.g. `AC`.
+    #[serde(deserialize_with = "deserialize_verdict")]
+    pub verdict: Verdict,
+    /// Why the verdict is UK, how the program terminated with RE, what the
+    /// program did with SV, or the missing output file with WA.
+    pub message: Option<String>,
+    /// Points ratio of PC.
+    pub points: Option<f64>,
+    pub wall_time_ms: Option<f64>,
+    pub cpu_time_ms: Option<f64>,
+    pub peak_memory_kib: Option<u64>,
+    pub exit_code: Option<i32>,
+    pub signal: Option<i32>,
+    pub input_file: Option<PathBuf>,
+    pub accepted_output_file: Option<PathBuf>,
+    pub tested_output_file: Option<PathBuf>,
+    /// The difference or checker message file.
+    pub log_file: Option<PathBuf>,
+    pub minimized_input_file: Option<PathBuf>,
+}
+
+/// Deserialize a verdict as one of `VERDICTS`.
+fn deserialize_verdict<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<&'static str, D::Error> {
+    let verdict = String::deserialize(deserializer)?;
+    VERDICTS
+        .into_iter()
+        .find(|name| *name == verdict)
+        .ok_or_else(|| serde::de::Error::custom(format!("unknown verdict `{}`", verdict)))
+}
+
+impl TestRecord {
+    /// Make the record of a launch result.
+    ///
+    /// `name` and `input_file` --- the name and input file of the suite.
+    ///
+    /// `tested_program` --- the tested program before compilation.
+    pub fn new(
+        result: &LaunchSuiteResult,
+        name: String,
+        tested_program: PathBuf,
+        input_file: PathBuf,
+        work_dir: &PathBuf,
+    ) -> Self {
+        let inner = &result.inner;
+        let usage = inner.usage();
+        let failure = match inner {
+            LaunchSuiteEnum::RE(_, failure) => Some(*failure),
+            _ => None,
+        };
+        Self {
+            index: result.index,
+            name,
+            program: result.program,
+            tested_program,
+            verdict: inner.name(),
+            message: match inner {
+                LaunchSuiteEnum::UK(hint) => Some(hint.to_owned()),
+                LaunchSuiteEnum::RE(_, failure) => Some(failure.to_string()),
+                LaunchSuiteEnum::SV(_) => Some("forbidden system call".into()),
+                LaunchSuiteEnum::OutputMissing(_, name) => {
+                    Some(format!("output file `{}` is missing", name))
+                }
+                _ => None,
+            },
+            points: match inner {
+                LaunchSuiteEnum::PC(_, points, ..) => Some(*points),
+                _ => None,
+            },
+            wall_time_ms: usage.map(|usage| usage.duration.as_secs_f64() * 1000.0),
+            cpu_time_ms: usage
+                .and_then(|usage| usage.cpu_time)
+                .map(|cpu_time| cpu_time.as_secs_f64() * 1000.0),
+            peak_memory_kib: usage.and_then(|usage| usage.peak_memory),
+            exit_code: match failure {
+                Some(ExitFailure::Code(code)) => Some(code),
+                _ => None,
+            },
+            signal: match failure {
+                Some(ExitFailure::Signal(signal)) => Some(signal),
+                _ => None,
+            },
+            input_file: Some(input_file),
+            accepted_output_file: Some(work_dir.join(format!("ac{}.out", result.index))),
+            tested_output_file: Some(result.tested_output.to_owned()),
+            log_file: inner.log_path().cloned(),
+            minimized_input_file: result.minimized_input.to_owned(),
+        }
+    }
+
+    /// Forget the files which don't exist (e.g. removed after the run).
+    pub fn forget_missing_files(&mut self) {
+        for file in [
+            &mut self.input_file,
+            &mut self.accepted_output_file,
+            &mut self.tested_output_file,
+            &mut self.log_file,
+            &mut self.minimized_input_file,
+        ] {
+            if file.as_ref().is_some_and(|file| !file.is_file()) {
+                *file = None;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_from() {
+        assert!(matches!(
+            Report::try_from("json=out/report.json"),
+            Ok(Report::Json(path)) if path.as_path() == std::path::Path::new("out/report.json")
+        ));
+        assert!(matches!(Report::try_from("junit"), Ok(Report::Junit(None))));
+        assert!(matches!(
+            Report::try_from("junit=ci/junit.xml"),
+            Ok(Report::Junit(Some(_)))
+        ));
+        assert!(matches!(Report::try_from("html"), Ok(Report::Html(None))));
+        assert!(Report::try_from("json=").is_err());
+        assert!(Report::try_from("json").is_err());
+        assert!(Report::try_from("xml=report.xml").is_err());
+        assert!(Report::try_from("report.json").is_err());
+    }
+
+    #[test]
+    fn test_deserialize_record() {
+        let record = r##"{"index":3,"name":"#03","program":0,"tested_program":"a.cpp",
+            "verdict":"WA","message":null,"points":null,"wall_time_ms":1.5,
+            "cpu_time_ms":null,"peak_memory_kib":null,"exit_code":null,"signal":null,
+            "input_file":"data3.in","accepted_output_file":null,
+            "tested_output_file":null,"log_file":"wa3.log","minimized_input_file":null}"##;
+        let record: TestRecord = serde_json::from_str(record).unwrap();
+        assert_eq!(record.index, 3);
+        assert_eq!(record.verdict, "WA");
+        let serialized = serde_json::to_string(&record).unwrap();
+        assert!(serde_json::from_str::<TestRecord>(&serialized).is_ok());
+        assert!(
+            serde_json::from_str::<TestRecord>(&serialized.replace("\"WA\"", "\"XX\"")).is_err()
+        );
+    }
+}
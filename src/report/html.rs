@@ -0,0 +1,334 @@
+//! Self-contained HTML report with side-by-side differences.
+
+use super::TestRecord;
+use crate::config::Config;
+use crate::prelude::*;
+use std::fmt::Write as _;
+
+/// Maximum lines of each output compared in a difference.
+const MAX_DIFF_LINES: usize = 1000;
+/// Maximum bytes of an input or log preview.
+const MAX_PREVIEW: usize = 4 * 1024;
+
+const STYLE: &'static str = "
+body { font-family: sans-serif; margin: 2em; color: #222; }
+table { border-collapse: collapse; }
+th, td { border: 1px solid #ccc; padding: 2px 8px; text-align: left; vertical-align: top; }
+pre { background: #f6f6f6; padding: 8px; overflow-x: auto; }
+.badge { display: inline-block; min-width: 3em; padding: 1px 6px; border-radius: 4px;
+  color: #fff; font-weight: bold; text-align: center; }
+.AC { background: #2e7d32; } .PC { background: #00838f; } .UK { background: #f9a825; }
+.WA, .PE, .TLE, .MLE, .RE, .SV { background: #c62828; }
+table.diff td { font-family: monospace; white-space: pre; border: none; }
+table.diff td.no { color: #888; text-align: right; }
+tr.removed td.text-left, tr.changed td.text-left { background: #fdd; }
+tr.added td.text-right, tr.changed td.text-right { background: #dfd; }
+";
+
+/// Escape the text for HTML.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// The beginning of a file, marked if it is truncated.
+fn preview(path: &PathBuf) -> String {
+    let mut content = fs::read(path).unwrap_or_default();
+    let truncated = content.len() > MAX_PREVIEW;
+    content.truncate(MAX_PREVIEW);
+    let mut preview = String::from_utf8_lossy(&content).to_string();
+    if truncated {
+        preview.push_str("\n...");
+    }
+    preview
+}
+
+/// A row of a side-by-side difference. Line numbers start from 1.
+#[derive(Debug, PartialEq)]
+enum DiffRow<'a> {
+    Same(usize, usize, &'a str),
+    /// Only in the left file.
+    Removed(usize, &'a str),
+    /// Only in the right file.
+    Added(usize, &'a str),
+    Changed(usize, &'a str, usize, &'a str),
+}
+
+/// Compare the lines by the longest common subsequence, pairing the removed
+/// and added lines between two same lines.
+fn diff_lines<'a>(left: &[&'a str], right: &[&'a str]) -> Vec<DiffRow<'a>> {
+    let (n, m) = (left.len(), right.len());
+    // lcs[i][j] = LCS length of left[i..] and right[j..]
+    let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if left[i] == right[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut rows = Vec::new();
+    let (mut removed, mut added) = (Vec::new(), Vec::new());
+    let flush = |rows: &mut Vec<DiffRow<'a>>, removed: &mut Vec<usize>, added: &mut Vec<usize>| {
+        for k in 0..removed.len().max(added.len()) {
+            rows.push(match (removed.get(k), added.get(k)) {
+                (Some(&i), Some(&j)) => DiffRow::Changed(i + 1, left[i], j + 1, right[j]),
+                (Some(&i), None) => DiffRow::Removed(i + 1, left[i]),
+                (None, Some(&j)) => DiffRow::Added(j + 1, right[j]),
+                (None, None) => unreachable!(),
+            });
+        }
+        removed.clear();
+        added.clear();
+    };
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && left[i] == right[j] {
+            flush(&mut rows, &mut removed, &mut added);
+            rows.push(DiffRow::Same(i + 1, j + 1, left[i]));
+            i += 1;
+            j += 1;
+        } else if j == m || (i < n && lcs[i + 1][j] >= lcs[i][j + 1]) {
+            removed.push(i);
+            i += 1;
+        } else {
+            added.push(j);
+            j += 1;
+        }
+    }
+    flush(&mut rows, &mut removed, &mut added);
+    rows
+}
+
+/// Write a side-by-side difference table of the two files.
+fn write_diff(html: &mut String, expected: &PathBuf, found: &PathBuf) {
+    let expected = String::from_utf8_lossy(&fs::read(expected).unwrap_or_default()).to_string();
+    let found = String::from_utf8_lossy(&fs::read(found).unwrap_or_default()).to_string();
+    let expected: Vec<&str> = expected.lines().collect();
+    let found: Vec<&str> = found.lines().collect();
+    let truncated = expected.len() > MAX_DIFF_LINES || found.len() > MAX_DIFF_LINES;
+    let rows = diff_lines(
+        &expected[..expected.len().min(MAX_DIFF_LINES)],
+        &found[..found.len().min(MAX_DIFF_LINES)],
+    );
+
+    html.push_str("<table class=\"diff\">\n");
+    html.push_str(
+        "<tr><th></th><th>Expected (accepted output)</th>\
+        <th></th><th>Found (tested output)</th></tr>\n",
+    );
+    for row in rows {
+        let (class, left, right) = match row {
+            DiffRow::Same(i, j, text) => ("same", Some((i, text)), Some((j, text))),
+            DiffRow::Removed(i, text) => ("removed", Some((i, text)), None),
+            DiffRow::Added(j, text) => ("added", None, Some((j, text))),
+            DiffRow::Changed(i, left, j, right) => ("changed", Some((i, left)), Some((j, right))),
+        };
+        let cells = |side: &str, line: Option<(usize, &str)>| match line {
+            Some((no, text)) => format!(
+                "<td class=\"no\">{}</td><td class=\"text-{}\">{}</td>",
+                no,
+                side,
+                escape(text)
+            ),
+            None => format!("<td class=\"no\"></td><td class=\"text-{}\"></td>", side),
+        };
+        let _ = writeln!(
+            html,
+            "<tr class=\"{}\">{}{}</tr>",
+            class,
+            cells("left", left),
+            cells("right", right)
+        );
+    }
+    html.push_str("</table>\n");
+    if truncated {
+        let _ = writeln!(
+            html,
+            "<p>Only the first {} lines are compared.</p>",
+            MAX_DIFF_LINES
+        );
+    }
+}
+
+/// Format a time in milliseconds.
+fn format_ms(time_ms: Option<f64>) -> String {
+    time_ms.map_or_else(String::new, |time_ms| format!("{:.3} ms", time_ms))
+}
+
+/// Write the HTML report to `path`. Each WA or PE test case shows its
+/// difference file, input preview and a side-by-side difference of the
+/// outputs.
+pub fn write(path: &PathBuf, config: &Config, summary: &crate::LaunchSummary) -> io::Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    let count = &summary.launch_result_count;
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+        <title>OI Checker Report</title>\n<style>{}</style>\n</head>\n<body>\n",
+        STYLE
+    );
+    let mut programs: Vec<(usize, &PathBuf)> = summary
+        .records
+        .iter()
+        .map(|record| (record.program, &record.tested_program))
+        .collect();
+    programs.sort();
+    programs.dedup();
+    let multiple = config.tested_programs.len() > 1;
+    html.push_str("<h1>OI Checker Report</h1>\n<p>Tested program:");
+    for (_, program) in programs.iter() {
+        let _ = write!(html, " <code>{}</code>", escape(&program.to_string_lossy()));
+    }
+    html.push_str("</p>\n");
+    html.push_str("<p>");
+    for (name, i) in [
+        ("AC", 0),
+        ("PC", 7),
+        ("UK", 1),
+        ("TLE", 2),
+        ("MLE", 4),
+        ("RE", 5),
+        ("SV", 8),
+        ("PE", 6),
+        ("WA", 3),
+    ] {
+        let _ = write!(
+            html,
+            "<span class=\"badge {0}\">{0}</span> {1} ",
+            name, count[i]
+        );
+    }
+    let _ = writeln!(html, "/ Total {}</p>", summary.total());
+
+    // With more than one tested program, each row shows the program.
+    let program_cell = |record: &TestRecord| {
+        if multiple {
+            format!(
+                "<td><code>{}</code></td>",
+                escape(&record.tested_program.to_string_lossy())
+            )
+        } else {
+            String::new()
+        }
+    };
+    let _ = writeln!(
+        html,
+        "<table>\n<tr><th>Test</th>{}<th>Verdict</th><th>CPU time</th>\
+        <th>Wall time</th><th>Peak memory</th><th>Message</th></tr>",
+        if multiple { "<th>Program</th>" } else { "" }
+    );
+    for record in summary.records.iter() {
+        let name = if record.verdict == "WA" || record.verdict == "PE" {
+            format!(
+                "<a href=\"#test-{}-{}\">{}</a>",
+                record.index,
+                record.program,
+                escape(&record.name)
+            )
+        } else {
+            escape(&record.name)
+        };
+        let _ = writeln!(
+            html,
+            "<tr><td>{0}</td>{7}<td><span class=\"badge {1}\">{1}</span>{2}</td>\
+            <td>{3}</td><td>{4}</td><td>{5}</td><td>{6}</td></tr>",
+            name,
+            record.verdict,
+            record
+                .points
+                .map_or_else(String::new, |points| format!(" {:.1}%", points * 100.0)),
+            format_ms(record.cpu_time_ms),
+            format_ms(record.wall_time_ms),
+            record
+                .peak_memory_kib
+                .map_or_else(String::new, |kib| format!("{:.3} MiB", kib as f64 / 1024.0)),
+            escape(record.message.as_deref().unwrap_or_default()),
+            program_cell(record)
+        );
+    }
+    html.push_str("</table>\n");
+
+    for record in summary.records.iter() {
+        if record.verdict != "WA" && record.verdict != "PE" {
+            continue;
+        }
+        let _ = writeln!(
+            html,
+            "<h2 id=\"test-{0}-{1}\">Test {2}{3} <span class=\"badge {4}\">{4}</span></h2>",
+            record.index,
+            record.program,
+            escape(&record.name),
+            if multiple {
+                format!(" [{}]", escape(&record.tested_program.to_string_lossy()))
+            } else {
+                String::new()
+            },
+            record.verdict
+        );
+        if let Some(log_file) = &record.log_file {
+            let _ = writeln!(
+                html,
+                "<h3>Difference ({})</h3>\n<pre>{}</pre>",
+                escape(&log_file.to_string_lossy()),
+                escape(&preview(log_file))
+            );
+        }
+        if let Some(input_file) = &record.input_file {
+            let _ = writeln!(
+                html,
+                "<h3>Input ({})</h3>\n<pre>{}</pre>",
+                escape(&input_file.to_string_lossy()),
+                escape(&preview(input_file))
+            );
+        }
+        if let (Some(expected), Some(found)) =
+            (&record.accepted_output_file, &record.tested_output_file)
+        {
+            html.push_str("<h3>Output</h3>\n");
+            write_diff(&mut html, expected, found);
+        }
+    }
+    html.push_str("</body>\n</html>\n");
+    fs::write(path, html)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_lines() {
+        use DiffRow::*;
+        assert_eq!(
+            diff_lines(&["1", "2", "3", "4"], &["1", "5", "3", "4", "6"]),
+            [
+                Same(1, 1, "1"),
+                Changed(2, "2", 2, "5"),
+                Same(3, 3, "3"),
+                Same(4, 4, "4"),
+                Added(5, "6")
+            ]
+        );
+        assert_eq!(
+            diff_lines(&["1", "2"], &["2"]),
+            [Removed(1, "1"), Same(2, 1, "2")]
+        );
+        assert_eq!(diff_lines(&[], &[]), []);
+    }
+}
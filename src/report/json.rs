@@ -0,0 +1,63 @@
+//! JSON report.
+
+use super::TestRecord;
+use crate::config::Config;
+use crate::prelude::*;
+use crate::score::Scores;
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct JsonReport<'a> {
+    version: &'static str,
+    config: &'a Config,
+    totals: Totals,
+    /// Scores of each tested program.
+    scores: Vec<Scores>,
+    tests: &'a [TestRecord],
+}
+
+#[derive(Serialize)]
+#[allow(non_snake_case)]
+struct Totals {
+    AC: u32,
+    PC: u32,
+    UK: u32,
+    TLE: u32,
+    MLE: u32,
+    RE: u32,
+    SV: u32,
+    PE: u32,
+    WA: u32,
+    total: u32,
+    /// Index of the first failing (not AC) suite.
+    first_failure: Option<u32>,
+}
+
+/// Write the JSON report to `path`.
+pub fn write(path: &PathBuf, config: &Config, summary: &crate::LaunchSummary) -> io::Result<()> {
+    let count = &summary.launch_result_count;
+    let report = JsonReport {
+        version: env!("CARGO_PKG_VERSION"),
+        config,
+        totals: Totals {
+            AC: count[0],
+            PC: count[7],
+            UK: count[1],
+            TLE: count[2],
+            MLE: count[4],
+            RE: count[5],
+            SV: count[8],
+            PE: count[6],
+            WA: count[3],
+            total: summary.total(),
+            first_failure: summary.first_failure,
+        },
+        scores: (0..config.tested_programs.len())
+            .map(|program| summary.scores(config, program))
+            .collect(),
+        tests: &summary.records,
+    };
+    let file = io::BufWriter::new(File::create(path)?);
+    serde_json::to_writer_pretty(file, &report)?;
+    Ok(())
+}
@@ -0,0 +1,165 @@
+//! JUnit XML report.
+
+use super::TestRecord;
+use crate::config::Config;
+use crate::launch::TimeMeasurement;
+use crate::prelude::*;
+use std::fmt::Write as _;
+
+/// Maximum bytes of a log file embedded in a `<failure>`.
+const MAX_EMBEDDED_LOG: usize = 64 * 1024;
+
+/// Escape the text for XML content and attributes, dropping characters which
+/// are not allowed in XML 1.0.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if c < ' ' || c == '\u{FFFE}' || c == '\u{FFFF}' => (),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Time of the test in seconds, measured as `ac_timeout` is.
+fn get_time(record: &TestRecord, time_measurement: TimeMeasurement) -> f64 {
+    let time_ms = match time_measurement {
+        TimeMeasurement::Cpu => record.cpu_time_ms.or(record.wall_time_ms),
+        TimeMeasurement::Wall => record.wall_time_ms,
+    };
+    time_ms.unwrap_or(0.0) / 1000.0
+}
+
+/// Body of a `<failure>`: the log file content, or the message.
+fn get_failure_body(record: &TestRecord) -> String {
+    let mut body = match &record.log_file {
+        Some(log_file) => fs::read(log_file)
+            .map(|mut content| {
+                content.truncate(MAX_EMBEDDED_LOG);
+                String::from_utf8_lossy(&content).to_string()
+            })
+            .unwrap_or_default(),
+        None => String::new(),
+    };
+    if let Some(message) = &record.message {
+        body.push_str(message);
+    }
+    if let Some(input_file) = &record.input_file {
+        let _ = write!(body, "\nInput file: {}", input_file.display());
+    }
+    body
+}
+
+/// Write the JUnit XML report to `path`, with a `<testsuite>` for each tested
+/// program. WA, PE, PC, TLE, MLE, RE and SV are failures, and UK is an error.
+pub fn write(path: &PathBuf, config: &Config, summary: &crate::LaunchSummary) -> io::Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    let count = &summary.launch_result_count;
+    let errors = count[1];
+    let failures = summary.total() - count[0] - errors;
+    let time: f64 = summary
+        .records
+        .iter()
+        .map(|record| get_time(record, config.time_measurement))
+        .sum();
+
+    let mut xml = String::new();
+    let _ = writeln!(xml, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    let _ = writeln!(
+        xml,
+        r#"<testsuites name="oi_checker" tests="{}" failures="{}" errors="{}" time="{:.3}">"#,
+        summary.total(),
+        failures,
+        errors,
+        time
+    );
+    for (program, count) in summary.program_result_count.iter().enumerate() {
+        let records: Vec<&TestRecord> = summary
+            .records
+            .iter()
+            .filter(|record| record.program == program)
+            .collect();
+        let Some(first) = records.first() else {
+            continue;
+        };
+        let suite_name = escape(&first.tested_program.to_string_lossy());
+        let tests: u32 = count.iter().sum();
+        let errors = count[1];
+        let time: f64 = records
+            .iter()
+            .map(|record| get_time(record, config.time_measurement))
+            .sum();
+        let _ = writeln!(
+            xml,
+            r#"  <testsuite name="{}" tests="{}" failures="{}" errors="{}" time="{:.3}">"#,
+            suite_name,
+            tests,
+            tests - count[0] - errors,
+            errors,
+            time
+        );
+        for record in records {
+            write_testcase(&mut xml, record, &suite_name, config.time_measurement);
+        }
+        xml.push_str("  </testsuite>\n");
+    }
+    xml.push_str("</testsuites>\n");
+    fs::write(path, xml)
+}
+
+/// Write a `<testcase>` of the record.
+fn write_testcase(
+    xml: &mut String,
+    record: &TestRecord,
+    suite_name: &str,
+    time_measurement: TimeMeasurement,
+) {
+    let _ = write!(
+        xml,
+        r#"    <testcase name="{}" classname="{}" time="{:.3}""#,
+        escape(&record.name),
+        suite_name,
+        get_time(record, time_measurement)
+    );
+    match record.verdict {
+        "AC" => xml.push_str("/>\n"),
+        "UK" => {
+            let _ = write!(
+                xml,
+                ">\n      <error type=\"UK\" message=\"{}\"/>\n    </testcase>\n",
+                escape(record.message.as_deref().unwrap_or_default())
+            );
+        }
+        verdict => {
+            let _ = write!(
+                xml,
+                ">\n      <failure type=\"{}\" message=\"{}\">{}</failure>\n    </testcase>\n",
+                verdict,
+                escape(record.message.as_deref().unwrap_or(verdict)),
+                escape(&get_failure_body(record))
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(
+            escape("a < b && c > \"d\"\n'e'\u{1b}[0m"),
+            "a &lt; b &amp;&amp; c &gt; &quot;d&quot;\n&apos;e&apos;[0m"
+        );
+    }
+}
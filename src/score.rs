@@ -0,0 +1,197 @@
+//! Subtasks and partial scoring.
+
+use crate::config::cf_parsing;
+use crate::prelude::*;
+use crate::report::TestRecord;
+use serde::Serialize;
+
+/// How the score of a subtask is calculated from its test cases.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScoringPolicy {
+    /// Each test case is worth the same part of the points.
+    Sum,
+    /// The points are multiplied by the lowest score ratio of the test cases.
+    Min,
+    /// The points are given only if all of the test cases are accepted.
+    All,
+}
+
+impl TryFrom<&str> for ScoringPolicy {
+    type Error = String;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "sum" => Ok(Self::Sum),
+            "min" => Ok(Self::Min),
+            "all" => Ok(Self::All),
+            s => Err(format!("`{}` is not allowed in field `subtask.policy`", s)),
+        }
+    }
+}
+
+impl Display for ScoringPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Sum => "sum",
+            Self::Min => "min",
+            Self::All => "all",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// A group of test cases with consecutive indexes.
+#[derive(Debug, Clone, Serialize)]
+pub struct Subtask {
+    /// The first index, starting from 1.
+    pub first: u32,
+    /// The last index (inclusive).
+    pub last: u32,
+    pub points: f64,
+    pub policy: ScoringPolicy,
+}
+
+impl Subtask {
+    /// Get the score of the subtask. `ratios` maps the index of each finished
+    /// test case to its score ratio (between 0 and 1). Unfinished test cases
+    /// score 0.
+    pub fn score(&self, ratios: &HashMap<u32, f64>) -> f64 {
+        let ratios = (self.first..=self.last).map(|i| ratios.get(&i).copied().unwrap_or(0.0));
+        let count = (self.last - self.first + 1) as f64;
+        let ratio = match self.policy {
+            ScoringPolicy::Sum => ratios.sum::<f64>() / count,
+            ScoringPolicy::Min => ratios.fold(1.0, f64::min),
+            ScoringPolicy::All => {
+                if ratios.into_iter().all(|ratio| ratio >= 1.0) {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+        };
+        self.points * ratio
+    }
+
+    /// Get the id (starting from 1) of the first subtask containing the index,
+    /// or 0 if there is none.
+    pub fn get_id(subtasks: &[Self], index: u32) -> usize {
+        subtasks
+            .iter()
+            .position(|subtask| (subtask.first..=subtask.last).contains(&index))
+            .map_or(0, |i| i + 1)
+    }
+}
+
+impl TryFrom<cf_parsing::SubtaskConfig> for Subtask {
+    type Error = String;
+    fn try_from(value: cf_parsing::SubtaskConfig) -> Result<Self, Self::Error> {
+        let [first, last] = value.range;
+        if first == 0 || first > last {
+            return Err(format!(
+                "Expected 1 <= first <= last in field `subtask.range`, found [{}, {}]",
+                first, last
+            ));
+        }
+        if value.points.is_nan() || value.points < 0.0 {
+            return Err(format!(
+                "Expected a non-negative number in field `subtask.points`, found {}",
+                value.points
+            ));
+        }
+        Ok(Self {
+            first,
+            last,
+            points: value.points,
+            policy: value.policy.as_str().try_into()?,
+        })
+    }
+}
+
+/// Scores of a run.
+#[derive(Debug, Serialize)]
+pub struct Scores {
+    /// Score of each subtask.
+    pub subtasks: Vec<f64>,
+    /// Total score out of 100.
+    pub total: f64,
+}
+
+impl Scores {
+    /// Score the records. Without subtasks, each of the `test_cases` is worth
+    /// the same part of 100 points.
+    pub fn new(subtasks: &[Subtask], records: &[TestRecord], test_cases: u32) -> Self {
+        let ratios: HashMap<u32, f64> = records
+            .iter()
+            .map(|record| {
+                let ratio = match record.verdict {
+                    "AC" => 1.0,
+                    "PC" => record.points.unwrap_or(0.0),
+                    _ => 0.0,
+                };
+                (record.index, ratio)
+            })
+            .collect();
+        if subtasks.is_empty() {
+            let whole = Subtask {
+                first: 1,
+                last: test_cases.max(1),
+                points: 100.0,
+                policy: ScoringPolicy::Sum,
+            };
+            return Self {
+                subtasks: Vec::new(),
+                total: whole.score(&ratios),
+            };
+        }
+        let scores: Vec<f64> = subtasks
+            .iter()
+            .map(|subtask| subtask.score(&ratios))
+            .collect();
+        let points: f64 = subtasks.iter().map(|subtask| subtask.points).sum();
+        let total = if points > 0.0 {
+            scores.iter().sum::<f64>() / points * 100.0
+        } else {
+            0.0
+        };
+        Self {
+            subtasks: scores,
+            total,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subtask(first: u32, last: u32, points: f64, policy: ScoringPolicy) -> Subtask {
+        Subtask {
+            first,
+            last,
+            points,
+            policy,
+        }
+    }
+
+    #[test]
+    fn test_score() {
+        let ratios: HashMap<u32, f64> = [(1, 1.0), (2, 0.5), (3, 1.0), (4, 0.0)].into();
+        assert_eq!(subtask(1, 2, 30.0, ScoringPolicy::Sum).score(&ratios), 22.5);
+        assert_eq!(subtask(1, 2, 30.0, ScoringPolicy::Min).score(&ratios), 15.0);
+        assert_eq!(subtask(1, 2, 30.0, ScoringPolicy::All).score(&ratios), 0.0);
+        assert_eq!(subtask(1, 1, 30.0, ScoringPolicy::All).score(&ratios), 30.0);
+        // Test #5 is unfinished.
+        assert_eq!(subtask(3, 5, 30.0, ScoringPolicy::Sum).score(&ratios), 10.0);
+    }
+
+    #[test]
+    fn test_get_id() {
+        let subtasks = [
+            subtask(1, 3, 40.0, ScoringPolicy::Sum),
+            subtask(4, 6, 60.0, ScoringPolicy::Min),
+        ];
+        assert_eq!(Subtask::get_id(&subtasks, 1), 1);
+        assert_eq!(Subtask::get_id(&subtasks, 6), 2);
+        assert_eq!(Subtask::get_id(&subtasks, 7), 0);
+    }
+}
@@ -15,3 +15,22 @@ pub const LINE_END: &'static str = if cfg!(target_os = "windows") {
 } else {
     "\n"
 };
+
+/// Serialize a duration as milliseconds.
+pub fn serialize_millis<S: serde::Serializer>(
+    duration: &std::time::Duration,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(duration.as_secs_f64() * 1000.0)
+}
+
+/// Serialize an optional duration as seconds.
+pub fn serialize_optional_secs<S: serde::Serializer>(
+    duration: &Option<std::time::Duration>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match duration {
+        Some(duration) => serializer.serialize_some(&duration.as_secs_f64()),
+        None => serializer.serialize_none(),
+    }
+}